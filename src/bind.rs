@@ -4,29 +4,46 @@ use std::mem::size_of;
 use std::ops::DerefMut;
 use std::os::raw::c_char;
 use std::ptr::null_mut;
-use std::{ffi::CString, ops::Deref};
+use std::{
+    ffi::{CStr, CString},
+    ops::Deref,
+};
 
 use anyhow::{anyhow, Result};
 use libduckdb_sys::{
-    duckdb_bind_add_result_column, duckdb_bind_get_parameter, duckdb_bind_get_parameter_count,
-    duckdb_bind_info, duckdb_bind_set_bind_data, duckdb_bind_set_error, duckdb_connect,
-    duckdb_connection, duckdb_create_logical_type, duckdb_create_table_function, duckdb_data_chunk,
+    duckdb_bind_add_result_column, duckdb_bind_get_extra_info, duckdb_bind_get_named_parameter,
+    duckdb_bind_get_parameter, duckdb_bind_get_parameter_count, duckdb_bind_info,
+    duckdb_bind_set_bind_data, duckdb_bind_set_error, duckdb_column_count, duckdb_column_name,
+    duckdb_column_type, duckdb_connect, duckdb_connection, duckdb_create_decimal_type,
+    duckdb_create_list_type, duckdb_create_logical_type, duckdb_create_map_type,
+    duckdb_create_table_function, duckdb_data_chunk, duckdb_data_chunk_get_size,
     duckdb_data_chunk_get_vector, duckdb_data_chunk_set_size, duckdb_database,
-    duckdb_delete_callback_t, duckdb_destroy_table_function, duckdb_destroy_value,
-    duckdb_function_get_bind_data, duckdb_function_get_init_data, duckdb_function_info,
-    duckdb_function_set_error, duckdb_get_int64, duckdb_get_varchar, duckdb_init_get_column_count,
-    duckdb_init_get_column_index, duckdb_init_info, duckdb_init_set_init_data, duckdb_logical_type,
-    duckdb_malloc, duckdb_register_table_function, duckdb_table_function,
-    duckdb_table_function_add_parameter, duckdb_table_function_bind_t,
-    duckdb_table_function_init_t, duckdb_table_function_set_bind,
+    duckdb_delete_callback_t, duckdb_destroy_data_chunk, duckdb_destroy_logical_type,
+    duckdb_destroy_pending, duckdb_destroy_prepare, duckdb_destroy_result,
+    duckdb_destroy_table_function, duckdb_destroy_value, duckdb_disconnect, duckdb_execute_pending,
+    duckdb_function_get_bind_data, duckdb_function_get_init_data,
+    duckdb_function_get_local_init_data, duckdb_function_info, duckdb_function_set_error,
+    duckdb_get_int64, duckdb_get_varchar, duckdb_init_get_bind_data, duckdb_init_get_column_count,
+    duckdb_init_get_column_index, duckdb_init_get_extra_info, duckdb_init_info,
+    duckdb_init_set_error, duckdb_init_set_init_data, duckdb_init_set_max_threads,
+    duckdb_list_vector_get_child, duckdb_list_vector_get_size, duckdb_list_vector_reserve,
+    duckdb_list_vector_set_size, duckdb_logical_type, duckdb_malloc, duckdb_pending_error,
+    duckdb_pending_prepared_streaming, duckdb_pending_result, duckdb_prepare, duckdb_prepare_error,
+    duckdb_prepared_statement, duckdb_query, duckdb_register_table_function, duckdb_result,
+    duckdb_result_error, duckdb_stream_fetch_chunk, duckdb_string_t,
+    duckdb_struct_vector_get_child, duckdb_table_function,
+    duckdb_table_function_add_named_parameter, duckdb_table_function_add_parameter,
+    duckdb_table_function_bind_t, duckdb_table_function_init_t, duckdb_table_function_set_bind,
     duckdb_table_function_set_extra_info, duckdb_table_function_set_function,
-    duckdb_table_function_set_init, duckdb_table_function_set_name,
-    duckdb_table_function_supports_projection_pushdown, duckdb_table_function_t, duckdb_value,
-    duckdb_vector, duckdb_vector_assign_string_element_len, duckdb_vector_get_data,
-    duckdb_vector_size, idx_t, DuckDBSuccess, DUCKDB_TYPE_DUCKDB_TYPE_BIGINT,
-    DUCKDB_TYPE_DUCKDB_TYPE_BLOB, DUCKDB_TYPE_DUCKDB_TYPE_BOOLEAN, DUCKDB_TYPE_DUCKDB_TYPE_DATE,
-    DUCKDB_TYPE_DUCKDB_TYPE_DECIMAL, DUCKDB_TYPE_DUCKDB_TYPE_DOUBLE, DUCKDB_TYPE_DUCKDB_TYPE_ENUM,
-    DUCKDB_TYPE_DUCKDB_TYPE_FLOAT, DUCKDB_TYPE_DUCKDB_TYPE_HUGEINT,
+    duckdb_table_function_set_init, duckdb_table_function_set_local_init,
+    duckdb_table_function_set_name, duckdb_table_function_supports_projection_pushdown,
+    duckdb_table_function_t, duckdb_type, duckdb_validity_row_is_valid,
+    duckdb_validity_set_row_invalid, duckdb_value, duckdb_vector,
+    duckdb_vector_assign_string_element_len, duckdb_vector_ensure_validity_writable,
+    duckdb_vector_get_data, duckdb_vector_get_validity, duckdb_vector_size, idx_t, DuckDBSuccess,
+    DUCKDB_TYPE_DUCKDB_TYPE_BIGINT, DUCKDB_TYPE_DUCKDB_TYPE_BLOB, DUCKDB_TYPE_DUCKDB_TYPE_BOOLEAN,
+    DUCKDB_TYPE_DUCKDB_TYPE_DATE, DUCKDB_TYPE_DUCKDB_TYPE_DECIMAL, DUCKDB_TYPE_DUCKDB_TYPE_DOUBLE,
+    DUCKDB_TYPE_DUCKDB_TYPE_ENUM, DUCKDB_TYPE_DUCKDB_TYPE_FLOAT, DUCKDB_TYPE_DUCKDB_TYPE_HUGEINT,
     DUCKDB_TYPE_DUCKDB_TYPE_INTEGER, DUCKDB_TYPE_DUCKDB_TYPE_INTERVAL,
     DUCKDB_TYPE_DUCKDB_TYPE_LIST, DUCKDB_TYPE_DUCKDB_TYPE_MAP, DUCKDB_TYPE_DUCKDB_TYPE_SMALLINT,
     DUCKDB_TYPE_DUCKDB_TYPE_STRUCT, DUCKDB_TYPE_DUCKDB_TYPE_TIME,
@@ -52,7 +69,6 @@ pub unsafe fn malloc_struct<T>() -> *mut T {
     duckdb_malloc(size_of::<T>()).cast::<T>()
 }
 
-///
 #[derive(Debug)]
 pub struct BindInfo(pub(crate) duckdb_bind_info);
 
@@ -71,6 +87,20 @@ impl BindInfo {
         unsafe { duckdb_bind_set_bind_data(self.0, data, free_function) }
     }
 
+    /// get named parameter, return None if not set in the query
+    pub fn get_named_parameter(&self, name: &str) -> Option<Value> {
+        let value = unsafe { duckdb_bind_get_named_parameter(self.0, as_string!(name)) };
+        if value.is_null() {
+            None
+        } else {
+            Some(Value::from(value))
+        }
+    }
+
+    pub fn get_extra_info<T>(&self) -> *mut T {
+        unsafe { duckdb_bind_get_extra_info(self.0).cast() }
+    }
+
     pub fn get_parameter_count(&self) -> u64 {
         unsafe { duckdb_bind_get_parameter_count(self.0) }
     }
@@ -95,7 +125,6 @@ impl Value {
         unsafe { CString::from_raw(duckdb_get_varchar(self.0)) }
     }
 
-    #[allow(unused)]
     pub fn get_int64(&self) -> i64 {
         unsafe { duckdb_get_int64(self.0) }
     }
@@ -166,6 +195,28 @@ impl LogicalType {
             ))
         }
     }
+
+    /// decimal with total number of digits and digits after the decimal point
+    pub fn decimal(width: u8, scale: u8) -> Self {
+        unsafe { Self(duckdb_create_decimal_type(width, scale)) }
+    }
+
+    /// list with elements of child type
+    pub fn list(child: &LogicalType) -> Self {
+        unsafe { Self(duckdb_create_list_type(child.0)) }
+    }
+
+    /// map from key type to value type
+    pub fn map(key: &LogicalType, value: &LogicalType) -> Self {
+        unsafe { Self(duckdb_create_map_type(key.0, value.0)) }
+    }
+}
+
+impl Drop for LogicalType {
+    fn drop(&mut self) {
+        // duckdb copies logical types it is given
+        unsafe { duckdb_destroy_logical_type(&mut self.0) }
+    }
 }
 
 impl Deref for LogicalType {
@@ -198,6 +249,13 @@ impl TableFunction {
         self
     }
 
+    pub fn add_named_parameter(&self, name: &str, logical_type: &LogicalType) -> &Self {
+        unsafe {
+            duckdb_table_function_add_named_parameter(self.0, as_string!(name), **logical_type);
+        }
+        self
+    }
+
     pub fn set_function(&self, func: duckdb_table_function_t) -> &Self {
         unsafe {
             duckdb_table_function_set_function(self.0, func);
@@ -212,6 +270,14 @@ impl TableFunction {
         self
     }
 
+    /// set thread local init, called once for each thread scanning the table
+    pub fn set_local_init(&self, init_func: duckdb_table_function_init_t) -> &Self {
+        unsafe {
+            duckdb_table_function_set_local_init(self.0, init_func);
+        }
+        self
+    }
+
     pub fn set_bind(&self, bind_func: duckdb_table_function_bind_t) -> &Self {
         unsafe {
             duckdb_table_function_set_bind(self.0, bind_func);
@@ -219,13 +285,11 @@ impl TableFunction {
         self
     }
 
-    #[allow(dead_code)]
     pub fn set_extra_info(&self, extra_info: *mut c_void, destroy: duckdb_delete_callback_t) {
         unsafe { duckdb_table_function_set_extra_info(self.0, extra_info, destroy) };
     }
 
     /// set projection
-    #[allow(unused)]
     pub fn set_projection_pushdown(&self, flag: bool) {
         unsafe { duckdb_table_function_supports_projection_pushdown(self.0, flag) };
     }
@@ -262,6 +326,10 @@ impl From<duckdb_database> for Database {
 }
 
 impl Database {
+    pub fn as_ptr(&self) -> duckdb_database {
+        self.0
+    }
+
     pub fn connect(&self) -> Result<Connection> {
         let mut connection: duckdb_connection = null_mut();
 
@@ -292,6 +360,139 @@ impl Connection {
         }
         Ok(())
     }
+
+    /// run query and return materialized result
+    pub fn query(&self, sql: &str) -> Result<QueryResult> {
+        let sql = CString::new(sql)?;
+        let mut result = QueryResult(unsafe { std::mem::zeroed() });
+        unsafe {
+            if duckdb_query(self.0, sql.as_ptr(), &mut result.0) != DuckDBSuccess {
+                let error = duckdb_result_error(&mut result.0);
+                if error.is_null() {
+                    return Err(anyhow!("Failed to run query"));
+                }
+                return Err(anyhow!(CStr::from_ptr(error).to_string_lossy().to_string()));
+            }
+        }
+        Ok(result)
+    }
+
+    /// run query and return streaming result, chunks are computed as they are fetched
+    pub fn query_streaming(&self, sql: &str) -> Result<QueryResult> {
+        let sql = CString::new(sql)?;
+        let mut result = QueryResult(unsafe { std::mem::zeroed() });
+        unsafe {
+            let mut prepared: duckdb_prepared_statement = null_mut();
+            if duckdb_prepare(self.0, sql.as_ptr(), &mut prepared) != DuckDBSuccess {
+                let error =
+                    error_message(duckdb_prepare_error(prepared), "Failed to prepare query");
+                duckdb_destroy_prepare(&mut prepared);
+                return Err(error);
+            }
+            let mut pending: duckdb_pending_result = null_mut();
+            if duckdb_pending_prepared_streaming(prepared, &mut pending) != DuckDBSuccess {
+                let error = error_message(duckdb_pending_error(pending), "Failed to run query");
+                duckdb_destroy_pending(&mut pending);
+                duckdb_destroy_prepare(&mut prepared);
+                return Err(error);
+            }
+            let state = duckdb_execute_pending(pending, &mut result.0);
+            duckdb_destroy_pending(&mut pending);
+            duckdb_destroy_prepare(&mut prepared);
+            if state != DuckDBSuccess {
+                return Err(error_message(
+                    duckdb_result_error(&mut result.0),
+                    "Failed to run query",
+                ));
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// error from duckdb message, default is used if there is no message
+unsafe fn error_message(error: *const c_char, default: &str) -> anyhow::Error {
+    if error.is_null() {
+        anyhow!(default.to_owned())
+    } else {
+        anyhow!(CStr::from_ptr(error).to_string_lossy().to_string())
+    }
+}
+
+/// connection is closed when dropped, functions registered with it stay in the catalog
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe {
+            duckdb_disconnect(&mut self.0);
+        }
+    }
+}
+
+pub struct QueryResult(duckdb_result);
+
+impl QueryResult {
+    pub fn column_count(&mut self) -> idx_t {
+        unsafe { duckdb_column_count(&mut self.0) }
+    }
+
+    pub fn column_type(&mut self, col: idx_t) -> duckdb_type {
+        unsafe { duckdb_column_type(&mut self.0, col) }
+    }
+
+    pub fn column_name(&mut self, col: idx_t) -> String {
+        unsafe {
+            let name = duckdb_column_name(&mut self.0, col);
+            if name.is_null() {
+                return String::new();
+            }
+            CStr::from_ptr(name).to_string_lossy().to_string()
+        }
+    }
+
+    /// fetch next chunk of streaming result, None when result is exhausted
+    pub fn fetch_chunk(&mut self) -> Result<Option<ResultChunk>> {
+        unsafe {
+            let chunk = duckdb_stream_fetch_chunk(self.0);
+            if !chunk.is_null() {
+                return Ok(Some(ResultChunk(chunk)));
+            }
+            let error = duckdb_result_error(&mut self.0);
+            if error.is_null() {
+                Ok(None)
+            } else {
+                Err(anyhow!(CStr::from_ptr(error).to_string_lossy().to_string()))
+            }
+        }
+    }
+}
+
+impl Drop for QueryResult {
+    fn drop(&mut self) {
+        unsafe {
+            duckdb_destroy_result(&mut self.0);
+        }
+    }
+}
+
+/// chunk fetched from query result
+pub struct ResultChunk(duckdb_data_chunk);
+
+impl ResultChunk {
+    pub fn size(&self) -> usize {
+        unsafe { duckdb_data_chunk_get_size(self.0) as usize }
+    }
+
+    pub fn get_vector<T>(&self, column_index: idx_t) -> Vector<T> {
+        Vector::from(unsafe { duckdb_data_chunk_get_vector(self.0, column_index) })
+    }
+}
+
+impl Drop for ResultChunk {
+    fn drop(&mut self) {
+        unsafe {
+            duckdb_destroy_data_chunk(&mut self.0);
+        }
+    }
 }
 
 pub struct FunctionInfo(duckdb_function_info);
@@ -311,6 +512,10 @@ impl FunctionInfo {
         unsafe { duckdb_function_get_init_data(self.0).cast() }
     }
 
+    pub fn get_local_init_data<T>(&self) -> *mut T {
+        unsafe { duckdb_function_get_local_init_data(self.0).cast() }
+    }
+
     pub fn set_error(&self, error: &str) {
         unsafe {
             duckdb_function_set_error(self.0, as_string!(error));
@@ -330,6 +535,25 @@ impl InitInfo {
         unsafe { duckdb_init_set_init_data(self.0, data, freeer) };
     }
 
+    pub fn get_bind_data<T>(&self) -> *mut T {
+        unsafe { duckdb_init_get_bind_data(self.0).cast() }
+    }
+
+    pub fn get_extra_info<T>(&self) -> *mut T {
+        unsafe { duckdb_init_get_extra_info(self.0).cast() }
+    }
+
+    pub fn set_error(&self, error: &str) {
+        unsafe {
+            duckdb_init_set_error(self.0, as_string!(error));
+        }
+    }
+
+    /// set maximum number of threads that can scan the table
+    pub fn set_max_threads(&self, max_threads: idx_t) {
+        unsafe { duckdb_init_set_max_threads(self.0, max_threads) };
+    }
+
     pub fn column_count(&self) -> idx_t {
         unsafe { duckdb_init_get_column_count(self.0) }
     }
@@ -359,6 +583,8 @@ impl DataChunk {
 
 pub struct Vector<T> {
     duck_ptr: duckdb_vector,
+    /// number of rows which can be written, larger than vector size for list children
+    capacity: usize,
     _phantom: PhantomData<T>,
 }
 
@@ -366,6 +592,7 @@ impl<T> From<duckdb_vector> for Vector<T> {
     fn from(duck_ptr: duckdb_vector) -> Self {
         Self {
             duck_ptr,
+            capacity: unsafe { duckdb_vector_size() } as usize,
             _phantom: PhantomData,
         }
     }
@@ -376,9 +603,84 @@ impl<T> Vector<T> {
     pub fn set_data(&self, row: usize, data: T) {
         let data_ptr: *mut T = unsafe { duckdb_vector_get_data(self.duck_ptr).cast() };
         let data_slice: &mut [T] =
-            unsafe { std::slice::from_raw_parts_mut(data_ptr, duckdb_vector_size() as usize) };
+            unsafe { std::slice::from_raw_parts_mut(data_ptr, self.capacity) };
         data_slice[row] = data;
     }
+
+    /// set row to null
+    pub fn set_null(&self, row: usize) {
+        unsafe {
+            duckdb_vector_ensure_validity_writable(self.duck_ptr);
+            let validity = duckdb_vector_get_validity(self.duck_ptr);
+            duckdb_validity_set_row_invalid(validity, row as idx_t);
+        }
+    }
+
+    /// same vector with different element type
+    pub fn cast<U>(&self) -> Vector<U> {
+        Vector {
+            duck_ptr: self.duck_ptr,
+            capacity: self.capacity,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// number of elements in all lists of list vector
+    pub fn list_size(&self) -> usize {
+        unsafe { duckdb_list_vector_get_size(self.duck_ptr) as usize }
+    }
+
+    /// grow child of list vector to hold size elements
+    pub fn set_list_size(&self, size: usize) -> Result<()> {
+        unsafe {
+            if duckdb_list_vector_reserve(self.duck_ptr, size as idx_t) != DuckDBSuccess
+                || duckdb_list_vector_set_size(self.duck_ptr, size as idx_t) != DuckDBSuccess
+            {
+                return Err(anyhow!("unable to resize list vector to {size}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// child of list vector, which holds elements of all lists
+    pub fn list_child<U>(&self) -> Vector<U> {
+        Vector {
+            duck_ptr: unsafe { duckdb_list_vector_get_child(self.duck_ptr) },
+            capacity: self.list_size(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// child of struct vector, map entries have key at 0 and value at 1
+    pub fn struct_child<U>(&self, index: idx_t) -> Vector<U> {
+        Vector {
+            duck_ptr: unsafe { duckdb_struct_vector_get_child(self.duck_ptr, index) },
+            capacity: self.capacity,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl Vector<duckdb_string_t> {
+    /// get varchar or blob element, None if it is null
+    pub fn get_bytes(&self, row: usize) -> Option<&[u8]> {
+        unsafe {
+            let validity = duckdb_vector_get_validity(self.duck_ptr);
+            if !validity.is_null() && !duckdb_validity_row_is_valid(validity, row as idx_t) {
+                return None;
+            }
+            let data_ptr: *const duckdb_string_t = duckdb_vector_get_data(self.duck_ptr).cast();
+            let string = &(*data_ptr.add(row)).value;
+            // strings up to 12 bytes are inlined
+            let length = string.inlined.length as usize;
+            let data = if length <= 12 {
+                string.inlined.inlined.as_ptr()
+            } else {
+                string.pointer.ptr.cast_const()
+            };
+            Some(std::slice::from_raw_parts(data.cast::<u8>(), length))
+        }
+    }
 }
 
 impl Vector<&[u8]> {
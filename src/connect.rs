@@ -0,0 +1,248 @@
+use std::boxed::Box;
+use std::ffi::c_void;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use libduckdb_sys::{
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+};
+use tracing::debug;
+
+use fluvio::config::{ConfigFile, TlsPaths};
+use fluvio::{Fluvio, FluvioConfig};
+
+use crate::bind::{
+    malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
+    TableFunction,
+};
+use crate::produce::close_producers;
+
+/// named parameters to select cluster, accepted by every function which connects to fluvio
+const CONNECT_PARAMETERS: &[&str] = &[
+    "profile",
+    "endpoint",
+    "tls_domain",
+    "tls_key",
+    "tls_cert",
+    "tls_ca_cert",
+];
+
+/// defaults set by fluvio_connect, used when function is called without connect parameters
+static SESSION_DEFAULT: Mutex<ConnectOpt> = Mutex::new(ConnectOpt {
+    profile: None,
+    endpoint: None,
+    tls_domain: None,
+    tls_key: None,
+    tls_cert: None,
+    tls_ca_cert: None,
+});
+
+/// add connect parameters to table function
+pub(crate) fn add_connect_parameters(table_function: &TableFunction) {
+    for name in CONNECT_PARAMETERS {
+        table_function.add_named_parameter(name, &LogicalType::new(DuckDBTypeEnum::Varchar));
+    }
+}
+
+/// how to connect to cluster. cluster is selected by endpoint, then by profile,
+/// and current profile of fluvio config is used if neither is set
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectOpt {
+    profile: Option<String>,
+    endpoint: Option<String>,
+    tls_domain: Option<String>,
+    tls_key: Option<String>,
+    tls_cert: Option<String>,
+    tls_ca_cert: Option<String>,
+}
+
+impl ConnectOpt {
+    /// connect parameters of the function, merged with session default
+    pub(crate) fn from_bind_info(bind_info: &BindInfo) -> Result<Self> {
+        let default = SESSION_DEFAULT.lock().expect("session lock").clone();
+        match Self::from_named_parameters(bind_info)? {
+            Some(opt) => Ok(opt.merge(default)),
+            None => Ok(default),
+        }
+    }
+
+    /// fill parameters which are not set from default. profile and endpoint both select
+    /// the cluster, so they are taken from default only if neither is set
+    fn merge(self, default: Self) -> Self {
+        let (profile, endpoint) = if self.profile.is_none() && self.endpoint.is_none() {
+            (default.profile, default.endpoint)
+        } else {
+            (self.profile, self.endpoint)
+        };
+        Self {
+            profile,
+            endpoint,
+            tls_domain: self.tls_domain.or(default.tls_domain),
+            tls_key: self.tls_key.or(default.tls_key),
+            tls_cert: self.tls_cert.or(default.tls_cert),
+            tls_ca_cert: self.tls_ca_cert.or(default.tls_ca_cert),
+        }
+    }
+
+    /// read connect parameters of the function, None if none is set
+    fn from_named_parameters(bind_info: &BindInfo) -> Result<Option<Self>> {
+        let mut values = vec![];
+        for name in CONNECT_PARAMETERS {
+            let value = match bind_info.get_named_parameter(name) {
+                Some(value) => Some(value.get_varchar().to_str()?.to_owned()),
+                None => None,
+            };
+            values.push(value);
+        }
+        if values.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+
+        let mut values = values.into_iter();
+        let mut next = || values.next().flatten();
+        Ok(Some(Self {
+            profile: next(),
+            endpoint: next(),
+            tls_domain: next(),
+            tls_key: next(),
+            tls_cert: next(),
+            tls_ca_cert: next(),
+        }))
+    }
+
+    /// resolve cluster config from endpoint, profile or current profile
+    pub(crate) fn cluster_config(&self) -> Result<FluvioConfig> {
+        let config = if let Some(endpoint) = &self.endpoint {
+            FluvioConfig::new(endpoint)
+        } else if let Some(profile) = &self.profile {
+            let config_file = ConfigFile::load_default_or_new()?;
+            config_file
+                .config()
+                .cluster_with_profile(profile)
+                .ok_or_else(|| anyhow!("profile not found: {profile}"))?
+                .clone()
+        } else {
+            FluvioConfig::load()?
+        };
+
+        match (&self.tls_key, &self.tls_cert, &self.tls_ca_cert) {
+            (None, None, None) => Ok(config),
+            (Some(key), Some(cert), Some(ca_cert)) => {
+                // domain defaults to host of endpoint
+                let domain = match &self.tls_domain {
+                    Some(domain) => domain.clone(),
+                    None => config
+                        .endpoint
+                        .split(':')
+                        .next()
+                        .unwrap_or_default()
+                        .to_owned(),
+                };
+                Ok(config.with_tls(TlsPaths {
+                    domain,
+                    key: PathBuf::from(key),
+                    cert: PathBuf::from(cert),
+                    ca_cert: PathBuf::from(ca_cert),
+                }))
+            }
+            _ => Err(anyhow!(
+                "tls_key, tls_cert and tls_ca_cert must be set together"
+            )),
+        }
+    }
+
+    pub(crate) async fn connect(&self) -> Result<Fluvio> {
+        let config = self.cluster_config()?;
+        debug!(endpoint = config.endpoint, "connecting to cluster");
+        Fluvio::connect_with_config(&config).await
+    }
+}
+
+/// set cluster used by other functions, e.g. fluvio_connect(profile := 'staging')
+pub fn fluvio_connect_function_def() -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_connect");
+    add_connect_parameters(&table_function);
+
+    table_function.set_function(Some(connect_read));
+    table_function.set_init(Some(connect_init));
+    table_function.set_bind(Some(connect_bind));
+    table_function
+}
+
+struct ConnectInner {
+    opt: ConnectOpt,
+    endpoint: String,
+}
+
+#[repr(C)]
+struct ConnectBindDataStruct(*mut ConnectInner);
+
+#[repr(C)]
+struct ConnectInitDataStruct {
+    done: bool,
+}
+
+#[no_mangle]
+unsafe extern "C" fn connect_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = internal_bind(&bind_info) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
+    bind_info.add_result_column("endpoint", LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    // without parameters, session default is reset to current profile
+    let opt = ConnectOpt::from_named_parameters(bind_info)?.unwrap_or_default();
+    // resolve config now, so invalid profile is reported before it's used
+    let endpoint = opt.cluster_config()?.endpoint;
+
+    let bind_inner = Box::new(ConnectInner { opt, endpoint });
+    let my_bind_data = malloc_struct::<ConnectBindDataStruct>();
+    (*my_bind_data).0 = Box::into_raw(bind_inner);
+    bind_info.set_bind_data(my_bind_data.cast(), Some(drop_my_bind_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
+    let actual = v.cast::<ConnectBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn connect_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
+
+    let my_init_data = malloc_struct::<ConnectInitDataStruct>();
+    (*my_init_data).done = false;
+    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
+}
+
+/// store session default and output endpoint of the cluster
+#[no_mangle]
+unsafe extern "C" fn connect_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
+    let info = FunctionInfo::from(info);
+    let output = DataChunk::from(chunk);
+    let bind_data = info.get_bind_data::<ConnectBindDataStruct>();
+    let inner = &*(*bind_data).0;
+    let init_data = info.get_init_data::<ConnectInitDataStruct>();
+    if (*init_data).done {
+        output.set_size(0);
+        return;
+    }
+
+    *SESSION_DEFAULT.lock().expect("session lock") = inner.opt.clone();
+    // connections of previous session are not reused
+    close_producers(None);
+    debug!(endpoint = inner.endpoint, "session default set");
+
+    let endpoint_vector = output.get_vector(0);
+    endpoint_vector.assign_string_element(0, inner.endpoint.as_bytes());
+    output.set_size(1);
+    (*init_data).done = true;
+}
@@ -1,64 +1,220 @@
 use std::boxed::Box;
+use std::collections::VecDeque;
 use std::ffi::c_void;
+use std::fmt::{Display, Formatter};
 use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
-use chrono::DateTime;
-use futures_lite::{Stream, StreamExt};
-use tracing::{debug, error, trace};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
+use futures_lite::{FutureExt, Stream, StreamExt};
+use tracing::{debug, trace};
 
-use jql::walker;
+use jql::{groups_walker, selectors_parser, Group};
 use libduckdb_sys::{
-    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
-    duckdb_vector_size, idx_t,
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_hugeint,
+    duckdb_init_info, duckdb_interval, duckdb_list_entry, duckdb_type, duckdb_vector_size, idx_t,
 };
 
-use fluvio::consumer::Record;
+use fluvio::consumer::{ConsumerConfigBuilder, Record};
 use fluvio::dataplane::link::ErrorCode;
-use fluvio::{ConsumerConfig, PartitionConsumer};
+use fluvio::dataplane::record::NO_TIMESTAMP;
+use fluvio::metadata::partition::{PartitionSpec, ReplicaKey};
+use fluvio::{ConsumerConfig, Fluvio, Offset, PartitionConsumer};
 use fluvio_future::task::run_block_on;
-use serde_json::Value;
+use fluvio_future::timer::sleep;
+use fluvio_types::PartitionId;
+use serde_json::{Map, Value};
 
 use crate::bind::{
     malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
-    TableFunction,
+    TableFunction, Vector,
 };
+use crate::connect::{add_connect_parameters, ConnectOpt};
+use crate::schema;
 
 pub fn fluvio_consumer_table_function_def() -> TableFunction {
     let table_function = TableFunction::new();
     table_function.set_name("fluvio_consume");
+    add_connect_parameters(&table_function);
 
-    // first parameter is topic name
+    // first parameter is topic name, optionally followed by command line options
     table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
-    // second paramter is offset
-    //   table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Bigint));
-    //third paramter is count
-    //   table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Bigint));
+    // rest of options can be passed as named parameters
+    for (name, _option, kind) in opt::NAMED_PARAMETERS {
+        table_function.add_named_parameter(name, &LogicalType::new(kind.logical_type()));
+    }
 
     table_function.set_function(Some(consumer_read));
     table_function.set_init(Some(consume_init));
+    table_function.set_local_init(Some(consume_local_init));
     table_function.set_bind(Some(consumer_bind));
-    //  table_function.set_projection_pushdown(true);
+    table_function.set_projection_pushdown(true);
+    // filter pushdown is not supported by C API, so offset ranges must be given as options
     table_function
 }
 
 type PartitionConsumerIteratorInner = Pin<Box<dyn Stream<Item = Result<Record, ErrorCode>> + Send>>;
 
-struct FluvioBindInner {
-    consumer_stream: PartitionConsumerIteratorInner,
+/// how long a scanning thread waits for next record of a partition before it checks
+/// whether enough rows were already read by other threads
+const RECORD_WAIT: Duration = Duration::from_millis(100);
+
+/// result of waiting for next record of a partition
+enum NextRecord {
+    Record(Result<Record, ErrorCode>),
+    /// no record arrived within wait time
+    Waiting,
+    /// partition is exhausted or end offset is passed
+    End,
+}
+
+/// record stream from a single partition
+struct PartitionStream {
+    partition: PartitionId,
+    stream: PartitionConsumerIteratorInner,
     _consumer: Box<PartitionConsumer>,
+    /// last offset to read (inclusive)
+    end_offset: Option<i64>,
+    /// last record timestamp in ms to read (inclusive)
+    end_timestamp: Option<i64>,
+    end_reached: bool,
+}
+
+impl PartitionStream {
+    /// wait up to RECORD_WAIT for next record, end when partition is exhausted or end offset is passed
+    fn next_record(&mut self) -> NextRecord {
+        if self.end_reached {
+            return NextRecord::End;
+        }
+        let mut stream = self.stream.as_mut();
+        debug!(partition = self.partition, "waiting for data from fluvio");
+        let record = run_block_on(async {
+            async { Some(stream.next().await) }
+                .or(async {
+                    sleep(RECORD_WAIT).await;
+                    None
+                })
+                .await
+        });
+        let record = match record {
+            Some(Some(record)) => record,
+            Some(None) => return NextRecord::End,
+            None => return NextRecord::Waiting,
+        };
+        if let (Ok(record), Some(end_offset)) = (&record, self.end_offset) {
+            if record.offset() > end_offset {
+                debug!(partition = self.partition, end_offset, "end offset passed");
+                self.end_reached = true;
+                return NextRecord::End;
+            }
+            // stop at end offset without waiting for next record
+            self.end_reached = record.offset() == end_offset;
+        }
+        // records without timestamp don't end the scan
+        if let (Ok(record), Some(end_timestamp)) = (&record, self.end_timestamp) {
+            if record.timestamp() > end_timestamp {
+                debug!(
+                    partition = self.partition,
+                    end_timestamp, "end timestamp passed"
+                );
+                self.end_reached = true;
+                return NextRecord::End;
+            }
+        }
+        NextRecord::Record(record)
+    }
+}
+
+struct FluvioBindInner {
+    /// partitions not yet claimed by any scanning thread
+    partition_streams: Mutex<VecDeque<PartitionStream>>,
     max_row_count: u64,
     columns: Vec<ColumnMapping>,
 }
 
+impl FluvioBindInner {
+    /// claim next unread partition for a scanning thread
+    fn claim_stream(&self) -> Option<PartitionStream> {
+        self.partition_streams
+            .lock()
+            .expect("partition streams lock")
+            .pop_front()
+    }
+
+    /// return idle partition and claim next one, so partitions waiting to be claimed are
+    /// read when there are fewer threads than partitions
+    fn swap_stream(&self, idle: PartitionStream) -> PartitionStream {
+        let mut streams = self
+            .partition_streams
+            .lock()
+            .expect("partition streams lock");
+        match streams.pop_front() {
+            Some(next) => {
+                streams.push_back(idle);
+                next
+            }
+            None => idle,
+        }
+    }
+}
+
 #[repr(C)]
 struct FluvioBindDataStruct(*mut FluvioBindInner);
 
+/// shared by all scanning threads
 #[repr(C)]
 struct FluvioInitDataStruct {
-    total_row: u64,
+    total_row: AtomicU64,
 }
 
+/// partition owned by a scanning thread
+struct FluvioLocalInner {
+    stream: Option<PartitionStream>,
+    /// output column index and index of its mapped column
+    projection: Vec<(idx_t, usize)>,
+    /// whether any projected column is mapped from json value
+    parse_json: bool,
+}
+
+impl FluvioLocalInner {
+    /// wait for next record from owned partition, claim another partition once it is exhausted.
+    /// end when there are no partitions left
+    fn next_record(&mut self, bind: &FluvioBindInner) -> NextRecord {
+        loop {
+            let partition_stream = match &mut self.stream {
+                Some(partition_stream) => partition_stream,
+                None => match bind.claim_stream() {
+                    Some(partition_stream) => self.stream.insert(partition_stream),
+                    None => return NextRecord::End,
+                },
+            };
+            match partition_stream.next_record() {
+                NextRecord::End => {
+                    debug!(
+                        partition = partition_stream.partition,
+                        "no more records in partition"
+                    );
+                    self.stream = None;
+                }
+                NextRecord::Waiting => {
+                    if let Some(idle) = self.stream.take() {
+                        self.stream = Some(bind.swap_stream(idle));
+                    }
+                    return NextRecord::Waiting;
+                }
+                record => return record,
+            }
+        }
+    }
+}
+
+#[repr(C)]
+struct FluvioLocalInitDataStruct(*mut FluvioLocalInner);
+
 /// read data from fluvio
 #[no_mangle]
 unsafe extern "C" fn consumer_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
@@ -71,64 +227,82 @@ unsafe extern "C" fn consumer_read(info: duckdb_function_info, chunk: duckdb_dat
 
 unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
     let bind_data = info.get_bind_data::<FluvioBindDataStruct>();
-    let inner = &mut *(*bind_data).0;
+    let inner = &*(*bind_data).0;
     let max_row_count = inner.max_row_count;
-    let mut init_data = info.get_init_data::<FluvioInitDataStruct>();
-
-    // current accumulated total
-    let mut accum_total = (*init_data).total_row;
-    if accum_total >= max_row_count {
-        debug!(accum_total, "done, max row reached");
-        output.set_size(0);
-        return Ok(());
-    }
+    let init_data = info.get_init_data::<FluvioInitDataStruct>();
+    let total_row = &(*init_data).total_row;
+    let local_data = info.get_local_init_data::<FluvioLocalInitDataStruct>();
+    let local = &mut *(*local_data).0;
 
     let max_len = duckdb_vector_size() as usize;
     let mut row: usize = 0;
-    loop {
+    while row < max_len {
         debug!("row: {}", row);
-        // if exceed max row or max duckdb len
-        if accum_total >= max_row_count || row >= max_len {
-            output.set_size(row as u64);
-            (*init_data).total_row = accum_total;
+        if total_row.load(Ordering::SeqCst) >= max_row_count {
+            debug!("done, max row reached");
             break;
-        } else {
-            let mut stream = inner.consumer_stream.as_mut();
-            debug!("waiting for data from fluvio");
-            // get first data from fluvio
-            let record_output = run_block_on(async { stream.next().await });
+        }
 
-            let record = match record_output {
-                Some(record) => record?,
-                None => {
-                    debug!("no more records");
-                    output.set_size(row as u64);
-                    (*init_data).total_row = max_row_count; // set to max so we can terminate
+        // get next data from fluvio
+        let record = match local.next_record(inner) {
+            NextRecord::Record(record) => record?,
+            NextRecord::Waiting => {
+                // return rows read so far, otherwise check max row again after next wait
+                if row > 0 {
                     break;
                 }
-            };
+                continue;
+            }
+            NextRecord::End => {
+                debug!("no more records");
+                break;
+            }
+        };
 
-            let offset = record.offset();
-            debug!(offset, "retrieved record offset");
-            trace!(
-                "offset: {offset}, value: {:?}",
-                std::str::from_utf8(record.value()).unwrap()
-            );
-
-            for (column_index, column) in inner.columns.iter().enumerate() {
-                if let Err(err) =
-                    column
-                        .mapping
-                        .map(&record, column_index as u64, row, output, &column.ty)
-                {
-                    info.set_error(&err.to_string());
+        // reserve row once record arrived, so threads together don't exceed max row
+        if total_row.fetch_add(1, Ordering::SeqCst) >= max_row_count {
+            debug!("done, max row reached");
+            break;
+        }
+
+        let offset = record.offset();
+        debug!(offset, "retrieved record offset");
+        trace!(
+            "offset: {offset}, value: {:?}",
+            std::str::from_utf8(record.value()).unwrap()
+        );
+
+        // parse json once for all columns mapped from it
+        let json = if local.parse_json {
+            match serde_json::from_slice::<Value>(record.value()) {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    debug!(%err, "record value is not json");
+                    None
                 }
             }
+        } else {
+            None
+        };
 
-            row += 1;
-            accum_total += 1;
+        // only map columns used in the query
+        for (output_index, column_index) in local.projection.iter() {
+            let column = &inner.columns[*column_index];
+            if let Err(err) = column.mapping.map(
+                &record,
+                json.as_ref(),
+                *output_index,
+                row,
+                output,
+                &column.ty,
+            ) {
+                info.set_error(&err.to_string());
+            }
         }
+
+        row += 1;
     }
+    output.set_size(row as u64);
 
     Ok(())
 }
@@ -137,16 +311,54 @@ unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
 unsafe extern "C" fn consume_init(info: duckdb_init_info) {
     let info = InitInfo::from(info);
 
-    let _column_count = info.column_count();
-    //println!("columt count: {}", column_count);
+    // partitions are read in parallel, one thread per partition
+    let bind_data = info.get_bind_data::<FluvioBindDataStruct>();
+    let partition_count = (*(*bind_data).0)
+        .partition_streams
+        .lock()
+        .expect("partition streams lock")
+        .len();
+    info.set_max_threads(partition_count as idx_t);
+
+    let my_init_data = malloc_struct::<FluvioInitDataStruct>();
+    (*my_init_data).total_row = AtomicU64::new(0);
+    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
+    debug!(partition_count, "consumer init done");
+}
+
+/// called for each scanning thread, claim a partition to read
+#[no_mangle]
+unsafe extern "C" fn consume_local_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
 
-    let _colum_1 = info.projected_column_index(0);
-    //println!("projected column: {}", colum_1);
+    let bind_data = info.get_bind_data::<FluvioBindDataStruct>();
+    let inner = &*(*bind_data).0;
+
+    // projected columns, skip row id or other virtual columns which are not mapped
+    let projection: Vec<(idx_t, usize)> = (0..info.column_count())
+        .map(|index| (index, info.projected_column_index(index) as usize))
+        .filter(|(_, column_index)| *column_index < inner.columns.len())
+        .collect();
+    let parse_json = projection
+        .iter()
+        .any(|(_, column_index)| inner.columns[*column_index].mapping.uses_json());
+    debug!(?projection, parse_json, "projected columns");
+
+    let local_inner = Box::new(FluvioLocalInner {
+        stream: inner.claim_stream(),
+        projection,
+        parse_json,
+    });
 
-    let mut my_init_data = malloc_struct::<FluvioInitDataStruct>();
-    (*my_init_data).total_row = 0;
-    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
-    debug!("consumer init done");
+    let my_local_data = malloc_struct::<FluvioLocalInitDataStruct>();
+    (*my_local_data).0 = Box::into_raw(local_inner);
+    info.set_init_data(my_local_data.cast(), Some(drop_my_local_data_struct));
+}
+
+unsafe extern "C" fn drop_my_local_data_struct(v: *mut c_void) {
+    let actual = v.cast::<FluvioLocalInitDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
 }
 
 const PARM_TOPIC_NAME: u64 = 0;
@@ -171,32 +383,78 @@ unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
     let ptr = topic_param.get_varchar();
     let cmd_args = ptr.to_str()?;
 
-    let consumer_opt = opt::ConsumeOpt::parse_from_string(cmd_args)?;
-    let config = consumer_opt.generate_config()?;
+    let named_args = opt::named_parameter_args(bind_info)?;
+    let mut consumer_opt = opt::ConsumeOpt::parse_from_string(cmd_args, named_args)?;
+    let config_builder = consumer_opt.config_builder()?;
+    let config = config_builder.build()?;
+    debug!("consume config: {:#?}", config);
     let start_offset = consumer_opt.calculate_offset()?;
+    let mut columns = consumer_opt.columns_mappings()?;
 
     let topic = consumer_opt.topic.clone();
-    let consumer = run_block_on(async { fluvio::consumer(topic, 0).await })?;
-    let boxed_consumer = Box::new(consumer);
-    debug!("consumer created");
+    let all_partitions = consumer_opt.all_partitions;
+    let partition = consumer_opt.partition;
+    let connect_opt = ConnectOpt::from_bind_info(bind_info)?;
+    let consumers = run_block_on(async {
+        let fluvio = connect_opt.connect().await?;
+        let partitions = if all_partitions {
+            topic_partitions(&fluvio, &topic).await?
+        } else {
+            vec![partition]
+        };
+        let mut consumers = vec![];
+        for partition in partitions {
+            consumers.push(fluvio.partition_consumer(topic.clone(), partition).await?);
+        }
+        Ok(consumers) as Result<Vec<PartitionConsumer>>
+    })?;
+    debug!(partitions = consumers.len(), "consumers created");
+
+    let mut streams = VecDeque::new();
+    let mut samples = vec![];
+    for consumer in consumers {
+        let boxed_consumer = Box::new(consumer);
+        // with --since, start offset is different for each partition
+        let offset = match consumer_opt.since {
+            Some(since) => run_block_on(offset_since(&boxed_consumer, since))?,
+            None => start_offset.clone(),
+        };
+        if let Some(sample_size) = consumer_opt.infer_schema {
+            let count = sample_size.saturating_sub(samples.len());
+            let mut sample_config = config_builder.clone();
+            sample_config.disable_continuous(true);
+            samples.extend(run_block_on(sample_values(
+                &boxed_consumer,
+                offset.clone(),
+                sample_config.build()?,
+                count,
+            ))?);
+        }
+        let consumer_stream = run_block_on(async {
+            boxed_consumer
+                .stream_with_config(offset, config.clone())
+                .await
+        })?;
+        streams.push_back(PartitionStream {
+            partition: boxed_consumer.partition(),
+            stream: consumer_stream.boxed(),
+            _consumer: boxed_consumer,
+            end_offset: consumer_opt.end,
+            end_timestamp: consumer_opt.until,
+            end_reached: false,
+        });
+    }
 
-    // add offset column which is integer
-    let columns = consumer_opt.columns_mappings();
+    if consumer_opt.infer_schema.is_some() {
+        consumer_opt.columns = schema::infer_columns(&samples)?;
+        columns = consumer_opt.columns_mappings()?;
+    }
     for column in columns.iter() {
-        bind_info.add_result_column(&column.name, LogicalType::new(column.ty.clone()));
+        bind_info.add_result_column(&column.name, column.ty.logical_type());
     }
 
-    let consumer_stream = run_block_on(async {
-        boxed_consumer
-            .stream_with_config(start_offset, config)
-            .await
-    })?;
-
-    let boxed_stream = consumer_stream.boxed();
-    // println!("consumer stream created");
     let bind_inner = Box::new(FluvioBindInner {
-        consumer_stream: boxed_stream,
-        _consumer: boxed_consumer,
+        partition_streams: Mutex::new(streams),
         max_row_count: consumer_opt.rows as u64,
         columns,
     });
@@ -210,51 +468,307 @@ unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
 }
 
 unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
-    // let actual = v.cast::<MyBindDataStruct>();
+    let actual = v.cast::<FluvioBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
     duckdb_free(v);
 }
 
+/// find partitions of topic from partition list
+async fn topic_partitions(fluvio: &Fluvio, topic: &str) -> Result<Vec<PartitionId>> {
+    let admin = fluvio.admin().await;
+    let partitions = admin.all::<PartitionSpec>().await?;
+    let mut partition_ids: Vec<PartitionId> = partitions
+        .into_iter()
+        .filter_map(|partition| {
+            let key: ReplicaKey = partition.name.try_into().ok()?;
+            let (partition_topic, partition_id) = key.split();
+            (partition_topic == topic).then_some(partition_id)
+        })
+        .collect();
+    if partition_ids.is_empty() {
+        return Err(anyhow!("no partitions found for topic: {topic}"));
+    }
+    partition_ids.sort_unstable();
+    Ok(partition_ids)
+}
+
+/// find offset of first record with timestamp at or after `since` (ms) by binary search.
+/// assumes record timestamps are increasing within the partition. records without timestamp
+/// are skipped by the search, so they are read together with next record which has one
+async fn offset_since(consumer: &PartitionConsumer, since: i64) -> Result<Offset> {
+    let first = match first_timed_record(consumer, Offset::beginning(), None).await? {
+        Some(record) => record,
+        None => return Ok(Offset::beginning()),
+    };
+    if first.timestamp() >= since {
+        return Ok(Offset::beginning());
+    }
+    let last = first_record(consumer, Offset::from_end(1))
+        .await?
+        .map(|record| record.offset())
+        .unwrap_or_else(|| first.offset());
+
+    // timed records before `low` are older than since, first matching record is at or before `high`
+    let mut low = first.offset() + 1;
+    let mut high = last + 1;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match first_timed_record(consumer, Offset::absolute(mid)?, Some(high)).await? {
+            Some(record) if record.timestamp() < since => low = record.offset() + 1,
+            _ => high = mid,
+        }
+    }
+    debug!(
+        partition = consumer.partition(),
+        since,
+        offset = low,
+        "found offset for timestamp"
+    );
+    Ok(Offset::absolute(low)?)
+}
+
+/// read values of up to count records from offset. if there are no records after offset,
+/// as when streaming new records, last records of partition are read instead
+async fn sample_values(
+    consumer: &PartitionConsumer,
+    offset: Offset,
+    config: ConsumerConfig,
+    count: usize,
+) -> Result<Vec<Vec<u8>>> {
+    if count == 0 {
+        return Ok(vec![]);
+    }
+    let mut values = vec![];
+    for offset in [
+        offset,
+        Offset::from_end(count.try_into().unwrap_or(u32::MAX)),
+    ] {
+        let stream = consumer.stream_with_config(offset, config.clone()).await?;
+        let mut stream = Box::pin(stream.take(count));
+        while let Some(record) = stream.next().await {
+            values.push(record?.value().to_vec());
+        }
+        if !values.is_empty() {
+            break;
+        }
+    }
+    debug!(
+        partition = consumer.partition(),
+        records = values.len(),
+        "sampled records"
+    );
+    Ok(values)
+}
+
+/// read first record at or after offset, return None if there are no records
+async fn first_record(consumer: &PartitionConsumer, offset: Offset) -> Result<Option<Record>> {
+    let config = ConsumerConfig::builder().disable_continuous(true).build()?;
+    let mut stream = Box::pin(consumer.stream_with_config(offset, config).await?);
+    Ok(stream.next().await.transpose()?)
+}
+
+/// read first record with timestamp at or after offset and before end offset,
+/// return None if there is no such record
+async fn first_timed_record(
+    consumer: &PartitionConsumer,
+    offset: Offset,
+    end: Option<i64>,
+) -> Result<Option<Record>> {
+    let config = ConsumerConfig::builder().disable_continuous(true).build()?;
+    let mut stream = Box::pin(consumer.stream_with_config(offset, config).await?);
+    while let Some(record) = stream.next().await.transpose()? {
+        if end.is_some_and(|end| record.offset() >= end) {
+            break;
+        }
+        if record.timestamp() != NO_TIMESTAMP {
+            return Ok(Some(record));
+        }
+    }
+    Ok(None)
+}
+
 pub(crate) struct ColumnMapping {
     pub(crate) name: String,
     pub(crate) mapping: Box<dyn MappingTrait>,
-    pub(crate) ty: DuckDBTypeEnum,
+    pub(crate) ty: ColumnType,
 }
 
 impl ColumnMapping {
-    /// create new mapping for column,
-    pub(crate) fn new(name_ty: String, mapping: Box<dyn MappingTrait>) -> Self {
-        // check if name contains type
-        let mut parts = name_ty.split(':');
-        let name = parts.next().unwrap().to_string(); // always name
-        let ty = if let Some(ty_string) = parts.next() {
-            match ty_string {
-                "i" => DuckDBTypeEnum::Integer,
-                "l" => DuckDBTypeEnum::Uinteger,
-                "f" => DuckDBTypeEnum::Float,
-                "d" => DuckDBTypeEnum::Double,
-                "s" => DuckDBTypeEnum::Varchar,
-                "t" => DuckDBTypeEnum::TimestampMs,
-                _ => DuckDBTypeEnum::Varchar,
-            }
-        } else {
-            DuckDBTypeEnum::Varchar
+    /// create new mapping for column, type is optionally set after name as in `speed:d`
+    pub(crate) fn new(name_ty: String, mapping: Box<dyn MappingTrait>) -> Result<Self> {
+        let (name, ty) = match name_ty.split_once(':') {
+            Some((name, code)) => (name.to_string(), code.parse()?),
+            None => (name_ty, DuckDBTypeEnum::Varchar.into()),
         };
 
-        Self { name, mapping, ty }
+        Ok(Self { name, mapping, ty })
+    }
+}
+
+/// type of mapped column
+#[derive(Debug, Clone)]
+pub(crate) enum ColumnType {
+    Primitive(DuckDBTypeEnum),
+    /// total number of digits and digits after the decimal point
+    Decimal {
+        width: u8,
+        scale: u8,
+    },
+    /// json array with items of the type
+    List(Box<ColumnType>),
+    /// json object with string keys and values of the type
+    Map(Box<ColumnType>),
+}
+
+impl ColumnType {
+    pub(crate) fn logical_type(&self) -> LogicalType {
+        match self {
+            Self::Primitive(ty) => LogicalType::new(ty.clone()),
+            Self::Decimal { width, scale } => LogicalType::decimal(*width, *scale),
+            Self::List(item_ty) => LogicalType::list(&item_ty.logical_type()),
+            Self::Map(value_ty) => LogicalType::map(
+                &LogicalType::new(DuckDBTypeEnum::Varchar),
+                &value_ty.logical_type(),
+            ),
+        }
+    }
+}
+
+impl From<DuckDBTypeEnum> for ColumnType {
+    fn from(ty: DuckDBTypeEnum) -> Self {
+        Self::Primitive(ty)
+    }
+}
+
+/// type codes accepted after column name, type name first
+const TYPE_CODES: &[(&[&str], DuckDBTypeEnum)] = &[
+    (&["varchar", "s"], DuckDBTypeEnum::Varchar),
+    (&["integer", "i", "int"], DuckDBTypeEnum::Integer),
+    (&["bigint", "l"], DuckDBTypeEnum::Bigint),
+    (&["ubigint", "ul"], DuckDBTypeEnum::Ubigint),
+    (&["float", "f"], DuckDBTypeEnum::Float),
+    (&["double", "d"], DuckDBTypeEnum::Double),
+    (&["timestamp_ms", "t"], DuckDBTypeEnum::TimestampMs),
+    (&["tinyint"], DuckDBTypeEnum::Tinyint),
+    (&["smallint"], DuckDBTypeEnum::Smallint),
+    (&["hugeint"], DuckDBTypeEnum::Hugeint),
+    (&["utinyint"], DuckDBTypeEnum::Utinyint),
+    (&["usmallint"], DuckDBTypeEnum::Usmallint),
+    (&["uinteger"], DuckDBTypeEnum::Uinteger),
+    (&["boolean", "bool"], DuckDBTypeEnum::Boolean),
+    (&["date"], DuckDBTypeEnum::Date),
+    (&["time"], DuckDBTypeEnum::Time),
+    (&["timestamp", "timestamp_us"], DuckDBTypeEnum::Timestamp),
+    (&["timestamp_s"], DuckDBTypeEnum::TimestampS),
+    (&["timestamp_ns"], DuckDBTypeEnum::TimestampNs),
+    (&["interval"], DuckDBTypeEnum::Interval),
+    (&["uuid"], DuckDBTypeEnum::Uuid),
+    (&["blob"], DuckDBTypeEnum::Blob),
+];
+
+/// type name, which is parsed back to same type
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Primitive(ty) => {
+                let name = TYPE_CODES
+                    .iter()
+                    .find(|(_, code_ty)| {
+                        code_ty.clone() as duckdb_type == ty.clone() as duckdb_type
+                    })
+                    .map(|(codes, _)| codes[0])
+                    .unwrap_or("varchar");
+                write!(f, "{name}")
+            }
+            Self::Decimal { width, scale } => write!(f, "decimal({width},{scale})"),
+            Self::List(item_ty) => write!(f, "list({item_ty})"),
+            Self::Map(value_ty) => write!(f, "map({value_ty})"),
+        }
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = anyhow::Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        let code = code.to_ascii_lowercase();
+        if let Some((_, ty)) = TYPE_CODES.iter().find(|(codes, _)| codes.contains(&&*code)) {
+            return Ok(ty.clone().into());
+        }
+
+        // list(type) and map(type), type of items or values defaults to varchar
+        for (nested, constructor) in [
+            ("list", Self::List as fn(Box<Self>) -> Self),
+            ("map", Self::Map),
+        ] {
+            if let Some(args) = code.strip_prefix(nested) {
+                let child = match args.strip_prefix('(').and_then(|a| a.strip_suffix(')')) {
+                    Some(child) => child.parse()?,
+                    None if args.is_empty() => DuckDBTypeEnum::Varchar.into(),
+                    None => {
+                        return Err(anyhow!(
+                            "invalid {nested} type `{code}`, expected {nested}(type)"
+                        ))
+                    }
+                };
+                return Ok(constructor(Box::new(child)));
+            }
+        }
+
+        // decimal or decimal(p,s), same default as duckdb
+        if let Some(args) = code.strip_prefix("decimal") {
+            let (width, scale) = match args.strip_prefix('(').and_then(|a| a.strip_suffix(')')) {
+                Some(args) => {
+                    let (width, scale) = args.split_once(',').unwrap_or((args, "0"));
+                    let width: u8 = width.trim().parse()?;
+                    let scale: u8 = scale.trim().parse()?;
+                    (width, scale)
+                }
+                None if args.is_empty() => (18, 3),
+                None => {
+                    return Err(anyhow!(
+                        "invalid decimal type `{code}`, expected decimal(p,s)"
+                    ))
+                }
+            };
+            if !(1..=38).contains(&width) || scale > width {
+                return Err(anyhow!(
+                    "invalid decimal type `{code}`, width must be 1 to 38 and scale at most width"
+                ));
+            }
+            return Ok(Self::Decimal { width, scale });
+        }
+
+        let supported = TYPE_CODES
+            .iter()
+            .flat_map(|(codes, _)| codes.iter())
+            .copied()
+            .chain(["decimal(p,s)", "list(type)", "map(type)"])
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(anyhow!(
+            "unknown column type `{code}`. Supported: {supported}"
+        ))
     }
 }
 
 /// map record to string
 pub(crate) trait MappingTrait {
-    // map record to chunk for column
+    // map record to chunk for column, json is parsed record value if any column uses it
     fn map(
         &self,
         record: &Record,
+        json: Option<&Value>,
         colum: idx_t,
         row: usize,
         output: &DataChunk,
-        ty: &DuckDBTypeEnum,
+        ty: &ColumnType,
     ) -> Result<()>;
+
+    /// whether column is mapped from parsed json value
+    fn uses_json(&self) -> bool {
+        false
+    }
 }
 
 struct OffsetMapper();
@@ -263,13 +777,32 @@ impl MappingTrait for OffsetMapper {
     fn map(
         &self,
         record: &Record,
+        _json: Option<&Value>,
         colum: idx_t,
         row: usize,
         output: &DataChunk,
-        _ty: &DuckDBTypeEnum,
+        _ty: &ColumnType,
     ) -> Result<()> {
         let offset_vector = output.get_vector(colum);
-        offset_vector.set_data(row, record.offset() as u32);
+        offset_vector.set_data(row, record.offset());
+        Ok(())
+    }
+}
+
+struct PartitionMapper();
+
+impl MappingTrait for PartitionMapper {
+    fn map(
+        &self,
+        record: &Record,
+        _json: Option<&Value>,
+        colum: idx_t,
+        row: usize,
+        output: &DataChunk,
+        _ty: &ColumnType,
+    ) -> Result<()> {
+        let partition_vector = output.get_vector(colum);
+        partition_vector.set_data(row, record.partition());
         Ok(())
     }
 }
@@ -280,13 +813,44 @@ impl MappingTrait for TimestampMapper {
     fn map(
         &self,
         record: &Record,
+        _json: Option<&Value>,
         colum: idx_t,
         row: usize,
         output: &DataChunk,
-        _ty: &DuckDBTypeEnum,
+        _ty: &ColumnType,
     ) -> Result<()> {
         let timestamp_vector = output.get_vector(colum);
-        timestamp_vector.set_data(row, record.timestamp() as u64);
+        if record.timestamp() == NO_TIMESTAMP {
+            timestamp_vector.set_null(row);
+        } else {
+            timestamp_vector.set_data(row, record.timestamp() as u64);
+        }
+        Ok(())
+    }
+}
+
+struct KeyMapper();
+
+impl MappingTrait for KeyMapper {
+    fn map(
+        &self,
+        record: &Record,
+        _json: Option<&Value>,
+        colum: idx_t,
+        row: usize,
+        output: &DataChunk,
+        ty: &ColumnType,
+    ) -> Result<()> {
+        let key_vector = output.get_vector(colum);
+        match record.key() {
+            // varchar must be valid utf-8, blob keeps key bytes as they are
+            Some(key) if matches!(ty, ColumnType::Primitive(DuckDBTypeEnum::Varchar)) => {
+                let key = String::from_utf8_lossy(key);
+                key_vector.assign_string_element(row as idx_t, key.as_bytes())
+            }
+            Some(key) => key_vector.assign_string_element(row as idx_t, key),
+            None => key_vector.set_null(row),
+        }
         Ok(())
     }
 }
@@ -297,10 +861,11 @@ impl MappingTrait for ValueMapper {
     fn map(
         &self,
         record: &Record,
+        _json: Option<&Value>,
         colum: idx_t,
         row: usize,
         output: &DataChunk,
-        _ty: &DuckDBTypeEnum,
+        _ty: &ColumnType,
     ) -> Result<()> {
         let value_vector = output.get_vector(colum);
         value_vector.assign_string_element(row as idx_t, record.value());
@@ -308,138 +873,367 @@ impl MappingTrait for ValueMapper {
     }
 }
 
-struct JqlMapper(String);
+/// parsed jql selector
+struct JqlMapper(Vec<Group>);
 
 impl JqlMapper {
-    pub(crate) fn new(jql: String) -> Self {
-        Self(jql)
+    /// create mapper from jql selector. plain dot separated path such as `payload.VP.lat`
+    /// is converted to selector `"payload"."VP"."lat"`. invalid selector is an error
+    pub(crate) fn new(jql: String) -> Result<Self> {
+        let selector = if jql.starts_with(['"', '.', '[', '{', '|']) {
+            jql
+        } else {
+            path_selector(&jql)
+        };
+        let groups = selectors_parser(&selector)
+            .map_err(|err| anyhow!("invalid column path `{selector}`: {err}"))?;
+        Ok(Self(groups))
+    }
+}
+
+/// convert plain path to jql selector. keys are separated by `.` and `\.` is a dot inside key.
+/// segments in brackets such as `[0]` select array items and are kept as is
+fn path_selector(path: &str) -> String {
+    let mut segments = vec![];
+    let mut segment = String::new();
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => segment.push(chars.next().unwrap_or('\\')),
+            '.' => segments.push(std::mem::take(&mut segment)),
+            c => segment.push(c),
+        }
     }
+    segments.push(segment);
+
+    segments
+        .into_iter()
+        .map(|segment| {
+            if segment.starts_with('[') && segment.ends_with(']') {
+                segment
+            } else {
+                format!("\"{}\"", segment.replace('"', "\\\""))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
 }
 
 impl MappingTrait for JqlMapper {
     fn map(
         &self,
-        record: &Record,
+        _record: &Record,
+        json: Option<&Value>,
         colum: idx_t,
         row: usize,
         output: &DataChunk,
-        ty: &DuckDBTypeEnum,
+        ty: &ColumnType,
     ) -> Result<()> {
-        let v: Value = serde_json::from_slice(record.value())?;
-        let find_value = match walker(&v, &self.0) {
+        let vector = output.get_vector::<()>(colum);
+        // record value which is not json is null
+        let v = match json {
+            Some(value) => value,
+            None => {
+                vector.set_null(row);
+                return Ok(());
+            }
+        };
+        // path missing in record is null
+        let find_value = match groups_walker(v, &self.0) {
             Ok(value) => value,
             Err(err) => {
-                let value_vector = output.get_vector(colum);
-                value_vector.assign_string_element(row as idx_t, err.as_bytes());
+                debug!(err, "path not found");
+                vector.set_null(row);
                 return Ok(());
             }
         };
 
-        match find_value {
-            Value::String(s) => {
-                debug!("string: {}", s);
-                match ty {
-                    DuckDBTypeEnum::TimestampMs => {
-                        // 2023-01-28T23:54:23.405Z
-                        let value_vector = output.get_vector(colum);
-                        // parse timestamp
-                        match DateTime::parse_from_rfc3339(&s) {
-                            Ok(dt) => {
-                                let timestamp = dt.timestamp_millis();
-                                value_vector.set_data(row, timestamp as u64);
-                            }
-                            Err(err) => {
-                                error!("error parsing timestamp: {}", err);
-                            }
-                        }
-                    }
-                    _ => {
-                        let value_vector = output.get_vector(colum);
-                        value_vector.assign_string_element(row as idx_t, s.as_bytes());
-                    }
-                }
-            }
-            Value::Number(n) => {
-                debug!("number: {}", n);
-                match ty {
-                    DuckDBTypeEnum::Integer => {
-                        if let Some(i) = n.as_i64() {
-                            let val = i as i32;
-                            let value_vector = output.get_vector(colum);
-                            value_vector.set_data(row, val);
-                        }
-                    }
-                    DuckDBTypeEnum::Uinteger => {
-                        if let Some(i) = n.as_i64() {
-                            let val = i as u64;
-                            let value_vector = output.get_vector(colum);
-                            value_vector.set_data(row, val);
-                        }
-                    }
-                    DuckDBTypeEnum::Float => {
-                        if let Some(f) = n.as_f64() {
-                            let val = f as f32;
-                            let value_vector = output.get_vector(colum);
-                            value_vector.set_data(row, val);
-                        }
-                    }
-                    DuckDBTypeEnum::Double => {
-                        if let Some(val) = n.as_f64() {
-                            let value_vector = output.get_vector(colum);
-                            value_vector.set_data(row, val);
-                        }
-                    }
-                    _ => {
-                        debug!("ignore number: {}", n);
-                    }
-                }
-            }
-            Value::Bool(b) => {
-                debug!("bool: {}", b);
-                let value_vector = output.get_vector(colum);
-                value_vector.set_data(row, b as u8);
-            }
-            Value::Null => {
-                debug!("null value");
-                match ty {
-                    DuckDBTypeEnum::Integer => {
-                        let value_vector = output.get_vector(colum);
-                        value_vector.set_data(row, 0_i32);
-                    }
-                    DuckDBTypeEnum::Uinteger => {
-                        let value_vector = output.get_vector(colum);
-                        value_vector.set_data(row, 0_i64);
-                    }
-                    DuckDBTypeEnum::Float => {
-                        let value_vector = output.get_vector(colum);
-                        value_vector.set_data(row, 0 as f64);
-                    }
-                    DuckDBTypeEnum::Double => {
-                        let value_vector = output.get_vector(colum);
-                        value_vector.set_data(row, 0 as f64);
-                    }
-                    DuckDBTypeEnum::Varchar => {
-                        let value_vector = output.get_vector(colum);
-                        let string = "null";
-                        value_vector.assign_string_element(row as idx_t, string.as_bytes());
-                    }
-                    _ => {}
-                }
-            }
-            Value::Object(_) => {
-                debug!("object: {:?}", find_value);
-                let value_vector = output.get_vector(colum);
-                value_vector.assign_string_element(row as idx_t, find_value.to_string().as_bytes());
-            }
-            _ => {
-                debug!("other: {:?}", find_value);
-                let value_vector = output.get_vector(colum);
-                value_vector.assign_string_element(row as idx_t, find_value.to_string().as_bytes());
-            }
+        if !write_json_value(&find_value, &vector, row, ty)? {
+            debug!(?ty, "null or not convertible: {}", find_value);
+            vector.set_null(row);
         }
 
         Ok(())
     }
+
+    fn uses_json(&self) -> bool {
+        true
+    }
+}
+
+/// write json value to column, returns false if value is null or can't be converted to column type
+fn write_json_value(
+    value: &Value,
+    vector: &Vector<()>,
+    row: usize,
+    ty: &ColumnType,
+) -> Result<bool> {
+    let ty = match (value, ty) {
+        (_, ColumnType::Primitive(ty)) => ty,
+        (_, ColumnType::Decimal { width, scale }) => {
+            return Ok(match json_decimal(value, *width, *scale) {
+                Some(decimal) => write_decimal(vector, row, *width, decimal),
+                None => false,
+            });
+        }
+        (Value::Array(items), ColumnType::List(item_ty)) => {
+            return write_list(vector, row, items, item_ty);
+        }
+        (Value::Object(object), ColumnType::Map(value_ty)) => {
+            return write_map(vector, row, object, value_ty);
+        }
+        _ => return Ok(false),
+    };
+
+    let written = match (value, ty) {
+        (Value::Null, _) => false,
+        (Value::String(s), DuckDBTypeEnum::Varchar | DuckDBTypeEnum::Blob) => {
+            let value_vector = vector.cast::<&[u8]>();
+            value_vector.assign_string_element(row as idx_t, s.as_bytes());
+            true
+        }
+        // 2023-01-28T23:54:23.405Z
+        (
+            Value::String(s),
+            DuckDBTypeEnum::Timestamp
+            | DuckDBTypeEnum::TimestampS
+            | DuckDBTypeEnum::TimestampMs
+            | DuckDBTypeEnum::TimestampNs,
+        ) => match DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => set_value(vector, row, epoch(&dt, ty)),
+            Err(err) => {
+                debug!(%err, "error parsing timestamp");
+                false
+            }
+        },
+        // numbers are epoch in unit of the column
+        (
+            Value::Number(n),
+            DuckDBTypeEnum::Timestamp
+            | DuckDBTypeEnum::TimestampS
+            | DuckDBTypeEnum::TimestampMs
+            | DuckDBTypeEnum::TimestampNs,
+        ) => set_value(vector, row, n.as_i64()),
+        // 2023-01-28, stored as days since epoch
+        (Value::String(s), DuckDBTypeEnum::Date) => {
+            let days = NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| NaiveDate::from_ymd_opt(1970, 1, 1).map(|epoch| date - epoch))
+                .and_then(|days| i32::try_from(days.num_days()).ok());
+            set_value(vector, row, days)
+        }
+        // 23:54:23.405, stored as microseconds since midnight
+        (Value::String(s), DuckDBTypeEnum::Time) => {
+            let micros = NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+                .ok()
+                .and_then(|time| NaiveTime::from_hms_opt(0, 0, 0).map(|midnight| time - midnight))
+                .and_then(|duration| duration.num_microseconds());
+            set_value(vector, row, micros)
+        }
+        // 1h 30m
+        (Value::String(s), DuckDBTypeEnum::Interval) => {
+            let interval = humantime::parse_duration(s)
+                .ok()
+                .and_then(|duration| i64::try_from(duration.as_micros()).ok())
+                .map(|micros| duckdb_interval {
+                    months: 0,
+                    days: 0,
+                    micros,
+                });
+            set_value(vector, row, interval)
+        }
+        (Value::String(s), DuckDBTypeEnum::Uuid) => {
+            set_value(vector, row, parse_uuid(s).map(hugeint))
+        }
+        (Value::Bool(b), DuckDBTypeEnum::Boolean) => set_value(vector, row, Some(*b)),
+        (Value::Number(n), DuckDBTypeEnum::Tinyint) => {
+            set_value(vector, row, n.as_i64().and_then(|i| i8::try_from(i).ok()))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Smallint) => {
+            set_value(vector, row, n.as_i64().and_then(|i| i16::try_from(i).ok()))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Integer) => {
+            set_value(vector, row, n.as_i64().and_then(|i| i32::try_from(i).ok()))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Bigint) => set_value(vector, row, n.as_i64()),
+        (Value::Number(n), DuckDBTypeEnum::Hugeint) => {
+            let value = n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from));
+            set_value(vector, row, value.map(hugeint))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Utinyint) => {
+            set_value(vector, row, n.as_u64().and_then(|u| u8::try_from(u).ok()))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Usmallint) => {
+            set_value(vector, row, n.as_u64().and_then(|u| u16::try_from(u).ok()))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Uinteger) => {
+            set_value(vector, row, n.as_u64().and_then(|u| u32::try_from(u).ok()))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Ubigint) => set_value(vector, row, n.as_u64()),
+        (Value::Number(n), DuckDBTypeEnum::Float) => {
+            set_value(vector, row, n.as_f64().map(|f| f as f32))
+        }
+        (Value::Number(n), DuckDBTypeEnum::Double) => set_value(vector, row, n.as_f64()),
+        // numbers, booleans, objects and arrays are written as json text
+        (_, DuckDBTypeEnum::Varchar | DuckDBTypeEnum::Blob) => {
+            let value_vector = vector.cast::<&[u8]>();
+            value_vector.assign_string_element(row as idx_t, value.to_string().as_bytes());
+            true
+        }
+        _ => false,
+    };
+    Ok(written)
+}
+
+/// append json array to list column
+fn write_list(vector: &Vector<()>, row: usize, items: &[Value], ty: &ColumnType) -> Result<bool> {
+    let offset = vector.list_size();
+    vector.set_list_size(offset + items.len())?;
+    let child = vector.list_child::<()>();
+    for (index, item) in items.iter().enumerate() {
+        if !write_json_value(item, &child, offset + index, ty)? {
+            child.set_null(offset + index);
+        }
+    }
+    let entry = duckdb_list_entry {
+        offset: offset as u64,
+        length: items.len() as u64,
+    };
+    Ok(set_value(vector, row, Some(entry)))
+}
+
+/// append json object to map column, keys are object keys
+fn write_map(
+    vector: &Vector<()>,
+    row: usize,
+    object: &Map<String, Value>,
+    ty: &ColumnType,
+) -> Result<bool> {
+    let offset = vector.list_size();
+    vector.set_list_size(offset + object.len())?;
+    let entries = vector.list_child::<()>();
+    let keys = entries.struct_child::<&[u8]>(0);
+    let values = entries.struct_child::<()>(1);
+    for (index, (key, item)) in object.iter().enumerate() {
+        keys.assign_string_element((offset + index) as idx_t, key.as_bytes());
+        if !write_json_value(item, &values, offset + index, ty)? {
+            values.set_null(offset + index);
+        }
+    }
+    let entry = duckdb_list_entry {
+        offset: offset as u64,
+        length: object.len() as u64,
+    };
+    Ok(set_value(vector, row, Some(entry)))
+}
+
+/// write converted value to column, returns false if conversion failed
+fn set_value<T>(vector: &Vector<()>, row: usize, value: Option<T>) -> bool {
+    match value {
+        Some(value) => {
+            vector.cast::<T>().set_data(row, value);
+            true
+        }
+        None => false,
+    }
+}
+
+/// timestamp as epoch in unit of timestamp type
+fn epoch(dt: &DateTime<FixedOffset>, ty: &DuckDBTypeEnum) -> Option<i64> {
+    match ty {
+        DuckDBTypeEnum::TimestampS => Some(dt.timestamp()),
+        DuckDBTypeEnum::TimestampMs => Some(dt.timestamp_millis()),
+        DuckDBTypeEnum::TimestampNs => dt
+            .timestamp()
+            .checked_mul(1_000_000_000)?
+            .checked_add(dt.timestamp_subsec_nanos().into()),
+        _ => Some(dt.timestamp_micros()),
+    }
+}
+
+fn hugeint(value: i128) -> duckdb_hugeint {
+    duckdb_hugeint {
+        lower: value as u64,
+        upper: (value >> 64) as i64,
+    }
+}
+
+/// parse uuid into value stored by duckdb, which flips the top bit so uuids sort as strings
+fn parse_uuid(s: &str) -> Option<i128> {
+    let hex: String = s.chars().filter(|c| *c != '-').collect();
+    if hex.len() != 32 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u128::from_str_radix(&hex, 16).ok()?;
+    Some((value ^ (1 << 127)) as i128)
+}
+
+/// json number or numeric string as decimal scaled to integer, None if it doesn't fit width
+fn json_decimal(value: &Value, width: u8, scale: u8) -> Option<i128> {
+    let decimal = match value {
+        Value::Number(n) => parse_decimal(&n.to_string(), scale)?,
+        Value::String(s) => parse_decimal(s.trim(), scale)?,
+        _ => return None,
+    };
+    (decimal.unsigned_abs() < 10_u128.pow(width.into())).then_some(decimal)
+}
+
+/// parse decimal text such as `-12.345` into integer with `scale` digits after the decimal point,
+/// rounding half away from zero
+fn parse_decimal(text: &str, scale: u8) -> Option<i128> {
+    // exponent notation is only produced for floats, so precision is already limited
+    if text.contains(['e', 'E']) {
+        let scaled = (text.parse::<f64>().ok()? * 10_f64.powi(scale.into())).round();
+        return (scaled.abs() < 1e38).then_some(scaled as i128);
+    }
+
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let scale = scale as usize;
+    let mut decimal: i128 = 0;
+    for digit in int_part
+        .bytes()
+        .chain(frac_part.bytes().chain(std::iter::repeat(b'0')).take(scale))
+    {
+        decimal = decimal
+            .checked_mul(10)?
+            .checked_add((digit - b'0').into())?;
+    }
+    if frac_part
+        .as_bytes()
+        .get(scale)
+        .is_some_and(|digit| *digit >= b'5')
+    {
+        decimal = decimal.checked_add(1)?;
+    }
+
+    Some(if negative { -decimal } else { decimal })
+}
+
+/// write decimal to column, storage size depends on width
+fn write_decimal(vector: &Vector<()>, row: usize, width: u8, decimal: i128) -> bool {
+    // decimal fits width, so casts don't truncate
+    match width {
+        1..=4 => set_value(vector, row, Some(decimal as i16)),
+        5..=9 => set_value(vector, row, Some(decimal as i32)),
+        10..=18 => set_value(vector, row, Some(decimal as i64)),
+        _ => set_value(vector, row, Some(hugeint(decimal))),
+    }
 }
 
 mod opt {
@@ -449,15 +1243,17 @@ mod opt {
     use clap::Parser;
 
     use fluvio::{
-        FluvioError, Isolation, Offset, SmartModuleContextData, SmartModuleInvocation,
-        SmartModuleInvocationWasm, SmartModuleKind,
+        Isolation, SmartModuleContextData, SmartModuleInvocation, SmartModuleInvocationWasm,
+        SmartModuleKind,
     };
     use fluvio_future::tracing::debug;
     use fluvio_smartengine::transformation::TransformationConfig;
-    use fluvio_types::PartitionId;
 
     use super::*;
 
+    /// pseudo path to map record key in column mapping
+    const KEY_PATH: &str = "$key";
+
     /// copy from Fluvio CLI
     ///
     /// By default, consume operates in "streaming" mode, where the command will remain
@@ -495,14 +1291,23 @@ mod opt {
 
         /// The absolute offset of the first record to begin consuming from
         #[clap(long, value_name = "integer", conflicts_with_all = &["beginning", "head", "tail"])]
-        pub start: Option<u32>,
+        pub start: Option<i64>,
 
         #[clap(long, default_value = "1000")]
         pub rows: u32,
 
         /// Consume records until end offset (inclusive)
         #[clap(long, value_name = "integer")]
-        pub end: Option<u32>,
+        pub end: Option<i64>,
+
+        /// Consume records starting from the first record at or after timestamp.
+        /// Timestamp is in RFC 3339 format, e.g. 2023-01-28T23:00:00Z
+        #[clap(long, value_name = "timestamp", value_parser=parse_timestamp, conflicts_with_all = &["beginning", "head", "start", "tail"])]
+        pub since: Option<i64>,
+
+        /// Consume records until timestamp (inclusive), in RFC 3339 format
+        #[clap(long, value_name = "timestamp", value_parser=parse_timestamp)]
+        pub until: Option<i64>,
 
         /// Maximum number of bytes to be retrieved
         #[clap(short = 'b', long = "maxbytes", value_name = "integer")]
@@ -559,21 +1364,35 @@ mod opt {
         #[clap(long, short, conflicts_with_all = &["smartmodule_group", "transforms_file"])]
         pub transform: Vec<String>,
 
-        /// column mapping, this will map to duckdb columns, if this not specific, then default column (key,timestamp, value)
-        /// this assume values json format
-        /// Eg. -c  ph=contact.ph -c addr=contact.addr -e
+        /// column mapping, this will map to duckdb columns, if this not specific, then default column (offset, timestamp, key, value)
+        /// this assume values json format, use `$key` to map record key instead
+        /// column type is optionally set after name with short code or duckdb type name
+        /// Eg. -c  ph=contact.ph -c addr=contact.addr -c id=$key -c speed:double=spd
         #[clap(
             short = 'c',
             long,
             value_parser=parse_key_val,
         )]
         pub columns: Vec<(String, String)>,
+
+        /// infer columns from json fields of first N records, default 100.
+        /// fields of nested objects are flattened, e.g. VP.lat is mapped to column VP_lat
+        /// Eg. --infer-schema=500
+        #[clap(
+            long,
+            num_args = 0..=1,
+            default_missing_value = "100",
+            conflicts_with = "columns"
+        )]
+        pub infer_schema: Option<usize>,
     }
 
     impl ConsumeOpt {
-        pub fn parse_from_string(input: &str) -> Result<Self> {
-            let wrapper = format!("ConsumeOpt {input}");
-            let args = wrapper.split_whitespace();
+        /// parse options from string, followed by extra arguments
+        pub fn parse_from_string(input: &str, extra_args: Vec<String>) -> Result<Self> {
+            let args = std::iter::once("ConsumeOpt".to_owned())
+                .chain(split_args(input)?)
+                .chain(extra_args);
             ConsumeOpt::try_parse_from(args).map_err(|err| err.into())
         }
 
@@ -581,20 +1400,27 @@ mod opt {
             if let Some(end_offset) = self.end {
                 if let Some(start_offset) = self.start {
                     if end_offset < start_offset {
-                        eprintln!(
-                            "Argument end-offset must be greater than or equal to specified start offset"
-                        );
-                        return Err(FluvioError::CrossingOffsets(start_offset, end_offset).into());
+                        return Err(anyhow!(
+                            "Argument end offset {end_offset} must be greater than or equal to start offset {start_offset}"
+                        ));
                     }
                 }
             }
 
+            if let (Some(since), Some(until)) = (self.since, self.until) {
+                if until < since {
+                    return Err(anyhow!(
+                        "Argument until must be greater than or equal to since"
+                    ));
+                }
+            }
+
             let offset = if self.beginning {
                 Offset::from_beginning(0)
             } else if let Some(offset) = self.head {
                 Offset::from_beginning(offset)
             } else if let Some(offset) = self.start {
-                Offset::absolute(offset as i64).unwrap()
+                Offset::absolute(offset)?
             } else if let Some(offset) = self.tail {
                 Offset::from_end(offset)
             } else {
@@ -604,39 +1430,74 @@ mod opt {
             Ok(offset)
         }
 
-        pub(crate) fn columns_mappings(&self) -> Vec<ColumnMapping> {
+        pub(crate) fn columns_mappings(&self) -> Result<Vec<ColumnMapping>> {
             let columns: Vec<ColumnMapping> = self
                 .columns
                 .iter()
                 .map(|(name, json_map)| {
-                    ColumnMapping::new(name.clone(), Box::new(JqlMapper::new(json_map.clone())))
+                    if json_map == KEY_PATH {
+                        let column = ColumnMapping::new(name.clone(), Box::new(KeyMapper()))?;
+                        // key is raw bytes, it is not converted to other types
+                        match column.ty {
+                            ColumnType::Primitive(
+                                DuckDBTypeEnum::Varchar | DuckDBTypeEnum::Blob,
+                            ) => Ok(column),
+                            _ => Err(anyhow!(
+                                "column {} of type {} can't be mapped from {KEY_PATH}, use varchar or blob",
+                                column.name,
+                                column.ty
+                            )),
+                        }
+                    } else {
+                        ColumnMapping::new(name.clone(), Box::new(JqlMapper::new(json_map.clone())?))
+                    }
                 })
-                .collect();
+                .collect::<Result<_>>()?;
 
-            if columns.is_empty() {
+            let mut columns = if columns.is_empty() {
                 vec![
                     ColumnMapping {
                         name: "offset".to_owned(),
                         mapping: Box::new(OffsetMapper()),
-                        ty: DuckDBTypeEnum::Integer,
+                        ty: DuckDBTypeEnum::Bigint.into(),
                     },
                     ColumnMapping {
                         name: "timestamp".to_owned(),
                         mapping: Box::new(TimestampMapper()),
-                        ty: DuckDBTypeEnum::TimestampMs,
+                        ty: DuckDBTypeEnum::TimestampMs.into(),
+                    },
+                    ColumnMapping {
+                        name: "key".to_owned(),
+                        mapping: Box::new(KeyMapper()),
+                        ty: DuckDBTypeEnum::Varchar.into(),
                     },
                     ColumnMapping {
                         name: "value".to_string(),
                         mapping: Box::new(ValueMapper()),
-                        ty: DuckDBTypeEnum::Varchar,
+                        ty: DuckDBTypeEnum::Varchar.into(),
                     },
                 ]
             } else {
                 columns
+            };
+
+            // records are merged from all partitions, so add partition column to tell them apart
+            if self.all_partitions {
+                columns.insert(
+                    0,
+                    ColumnMapping {
+                        name: "partition".to_owned(),
+                        mapping: Box::new(PartitionMapper()),
+                        ty: DuckDBTypeEnum::Uinteger.into(),
+                    },
+                );
             }
+
+            Ok(columns)
         }
 
-        pub fn generate_config(&self) -> Result<ConsumerConfig> {
+        /// consumer config builder, so config can be adjusted for sampling
+        pub fn config_builder(&self) -> Result<ConsumerConfigBuilder> {
             let mut builder = ConsumerConfig::builder();
             if let Some(max_bytes) = self.max_bytes {
                 builder.max_bytes(max_bytes);
@@ -649,7 +1510,20 @@ mod opt {
 
             let smart_module = if let Some(smart_module_name) = &self.smartmodule {
                 vec![create_smartmodule(
-                    smart_module_name,
+                    SmartModuleInvocationWasm::Predefined(smart_module_name.clone()),
+                    self.smart_module_ctx(),
+                    initial_param,
+                )]
+            } else if let Some(smart_module_path) = &self.smartmodule_path {
+                let raw_buffer = std::fs::read(smart_module_path).map_err(|err| {
+                    anyhow!(format!(
+                        "unable to read smartmodule file `{}`: {err}",
+                        smart_module_path.display()
+                    ))
+                })?;
+                debug!(len = raw_buffer.len(), "read smartmodule wasm file");
+                vec![create_smartmodule(
+                    SmartModuleInvocationWasm::adhoc_from_bytes(&raw_buffer)?,
                     self.smart_module_ctx(),
                     initial_param,
                 )]
@@ -678,10 +1552,7 @@ mod opt {
                 builder.isolation(isolation);
             }
 
-            let consume_config = builder.build()?;
-            debug!("consume config: {:#?}", consume_config);
-
-            Ok(consume_config)
+            Ok(builder)
         }
 
         fn smart_module_ctx(&self) -> SmartModuleContextData {
@@ -695,6 +1566,190 @@ mod opt {
         }
     }
 
+    /// split option string into arguments using shell quoting rules.
+    /// single quotes are literal, double quotes allow escaping `"` and `\\`
+    fn split_args(input: &str) -> Result<Vec<String>> {
+        let mut args = vec![];
+        let mut current: Option<String> = None;
+        // positions are reported 1-based
+        let mut chars = input.chars().enumerate().map(|(pos, c)| (pos + 1, c));
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                c if c.is_whitespace() => {
+                    if let Some(arg) = current.take() {
+                        args.push(arg);
+                    }
+                }
+                '\'' => {
+                    let arg = current.get_or_insert_with(String::new);
+                    loop {
+                        match chars.next() {
+                            Some((_, '\'')) => break,
+                            Some((_, c)) => arg.push(c),
+                            None => {
+                                return Err(anyhow!(
+                                    "unbalanced single quote at position {pos}: {input}"
+                                ))
+                            }
+                        }
+                    }
+                }
+                '"' => {
+                    let arg = current.get_or_insert_with(String::new);
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, '\\')) => match chars.next() {
+                                Some((_, c @ ('"' | '\\'))) => arg.push(c),
+                                Some((_, c)) => {
+                                    arg.push('\\');
+                                    arg.push(c);
+                                }
+                                None => {
+                                    return Err(anyhow!(
+                                        "unbalanced double quote at position {pos}: {input}"
+                                    ))
+                                }
+                            },
+                            Some((_, c)) => arg.push(c),
+                            None => {
+                                return Err(anyhow!(
+                                    "unbalanced double quote at position {pos}: {input}"
+                                ))
+                            }
+                        }
+                    }
+                }
+                '\\' => match chars.next() {
+                    Some((_, c)) => current.get_or_insert_with(String::new).push(c),
+                    None => {
+                        return Err(anyhow!(
+                        "escape character without following character at position {pos}: {input}"
+                    ))
+                    }
+                },
+                c => current.get_or_insert_with(String::new).push(c),
+            }
+        }
+        if let Some(arg) = current {
+            args.push(arg);
+        }
+        Ok(args)
+    }
+
+    /// how named parameter is converted to command line option
+    pub(crate) enum NamedParameterKind {
+        /// boolean flag
+        Flag,
+        Integer,
+        Text,
+        /// JSON object passed as varchar, since C API can't read STRUCT or MAP values.
+        /// option is repeated with key=value for each entry
+        KeyValues,
+    }
+
+    impl NamedParameterKind {
+        pub(crate) fn logical_type(&self) -> DuckDBTypeEnum {
+            match self {
+                Self::Flag => DuckDBTypeEnum::Boolean,
+                Self::Integer => DuckDBTypeEnum::Bigint,
+                Self::Text | Self::KeyValues => DuckDBTypeEnum::Varchar,
+            }
+        }
+    }
+
+    /// named parameters of fluvio_consume with matching command line option
+    pub(crate) const NAMED_PARAMETERS: &[(&str, &str, NamedParameterKind)] = &[
+        ("partition", "--partition", NamedParameterKind::Integer),
+        (
+            "all_partitions",
+            "--all-partitions",
+            NamedParameterKind::Flag,
+        ),
+        (
+            "continuous",
+            "--enable-continuous",
+            NamedParameterKind::Flag,
+        ),
+        ("beginning", "--beginning", NamedParameterKind::Flag),
+        ("head", "--head", NamedParameterKind::Integer),
+        ("tail", "--tail", NamedParameterKind::Integer),
+        ("start", "--start", NamedParameterKind::Integer),
+        ("end", "--end", NamedParameterKind::Integer),
+        ("since", "--since", NamedParameterKind::Text),
+        ("until", "--until", NamedParameterKind::Text),
+        ("rows", "--rows", NamedParameterKind::Integer),
+        ("max_bytes", "--maxbytes", NamedParameterKind::Integer),
+        ("isolation", "--isolation", NamedParameterKind::Text),
+        ("smartmodule", "--smartmodule", NamedParameterKind::Text),
+        (
+            "smartmodule_path",
+            "--smartmodule-path",
+            NamedParameterKind::Text,
+        ),
+        (
+            "aggregate_initial",
+            "--aggregate-initial",
+            NamedParameterKind::Text,
+        ),
+        ("params", "--params", NamedParameterKind::KeyValues),
+        (
+            "transforms_file",
+            "--transforms-file",
+            NamedParameterKind::Text,
+        ),
+        ("transform", "--transform", NamedParameterKind::Text),
+        ("columns", "--columns", NamedParameterKind::KeyValues),
+        (
+            "infer_schema",
+            "--infer-schema",
+            NamedParameterKind::Integer,
+        ),
+    ];
+
+    /// convert named parameters set in the query to command line arguments
+    pub(crate) fn named_parameter_args(bind_info: &BindInfo) -> Result<Vec<String>> {
+        let mut args = vec![];
+        for (name, option, kind) in NAMED_PARAMETERS {
+            let value = match bind_info.get_named_parameter(name) {
+                Some(value) => value,
+                None => continue,
+            };
+            match kind {
+                NamedParameterKind::Flag => {
+                    if value.get_int64() != 0 {
+                        args.push(option.to_string());
+                    }
+                }
+                NamedParameterKind::Integer => {
+                    args.push(option.to_string());
+                    args.push(value.get_int64().to_string());
+                }
+                NamedParameterKind::Text => {
+                    args.push(option.to_string());
+                    args.push(value.get_varchar().to_str()?.to_owned());
+                }
+                NamedParameterKind::KeyValues => {
+                    let json = value.get_varchar();
+                    let entries: serde_json::Map<String, Value> =
+                        serde_json::from_str(json.to_str()?).map_err(|err| {
+                            anyhow!("parameter `{name}` must be a JSON object: {err}")
+                        })?;
+                    for (key, entry) in entries {
+                        let entry = match entry {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        };
+                        args.push(option.to_string());
+                        args.push(format!("{key}={entry}"));
+                    }
+                }
+            }
+        }
+        debug!(?args, "named parameter args");
+        Ok(args)
+    }
+
     fn parse_key_val(s: &str) -> Result<(String, String)> {
         let pos = s
             .find('=')
@@ -702,6 +1757,14 @@ mod opt {
         Ok((s[..pos].parse()?, s[pos + 1..].parse()?))
     }
 
+    /// parse RFC 3339 timestamp into milliseconds since epoch
+    fn parse_timestamp(s: &str) -> Result<i64> {
+        let timestamp = DateTime::parse_from_rfc3339(s).map_err(|err| {
+            anyhow!("invalid timestamp `{s}`, expected format like 2023-01-28T23:00:00Z: {err}")
+        })?;
+        Ok(timestamp.timestamp_millis())
+    }
+
     pub(crate) fn parse_isolation(s: &str) -> Result<Isolation, String> {
         match s {
             "read_committed" | "ReadCommitted" | "readCommitted" | "readcommitted" => Ok(Isolation::ReadCommitted),
@@ -711,12 +1774,12 @@ mod opt {
     }
 
     fn create_smartmodule(
-        name: &str,
+        wasm: SmartModuleInvocationWasm,
         ctx: SmartModuleContextData,
         params: BTreeMap<String, String>,
     ) -> SmartModuleInvocation {
         SmartModuleInvocation {
-            wasm: SmartModuleInvocationWasm::Predefined(name.to_string()),
+            wasm,
             kind: SmartModuleKind::Generic(ctx),
             params: params.into(),
         }
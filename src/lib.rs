@@ -1,7 +1,14 @@
 mod bind;
+mod connect;
 mod consume;
 mod partition;
+mod produce;
+mod schema;
+mod smartengine;
+mod smartmodule;
+mod spu;
 mod topic;
+mod topic_admin;
 
 mod top {
     use std::ffi::c_char;
@@ -11,8 +18,15 @@ mod top {
     use tracing::info;
 
     use crate::{
-        bind::Database, partition::fluvio_admin_partition_function_def,
+        bind::Database,
+        connect::fluvio_connect_function_def,
+        partition::fluvio_admin_partition_function_def,
+        produce::fluvio_produce_function_def,
+        smartengine::fluvio_smartmodule_apply_function_def,
+        smartmodule::fluvio_admin_smartmodule_function_def,
+        spu::fluvio_admin_spu_function_def,
         topic::fluvio_admin_topic_function_def,
+        topic_admin::{fluvio_create_topic_function_def, fluvio_delete_topic_function_def},
     };
 
     use super::consume::fluvio_consumer_table_function_def;
@@ -34,9 +48,16 @@ mod top {
         fluvio_future::subscriber::init_tracer(None);
         let db = Database::from(db);
         let connection = db.connect()?;
+        connection.register_table_function(fluvio_connect_function_def())?;
         connection.register_table_function(fluvio_consumer_table_function_def())?;
         connection.register_table_function(fluvio_admin_topic_function_def())?;
         connection.register_table_function(fluvio_admin_partition_function_def())?;
+        connection.register_table_function(fluvio_create_topic_function_def())?;
+        connection.register_table_function(fluvio_delete_topic_function_def())?;
+        connection.register_table_function(fluvio_admin_spu_function_def())?;
+        connection.register_table_function(fluvio_admin_smartmodule_function_def())?;
+        connection.register_table_function(fluvio_smartmodule_apply_function_def(&db))?;
+        connection.register_table_function(fluvio_produce_function_def(&db))?;
         Ok(())
     }
 }
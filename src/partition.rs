@@ -4,23 +4,28 @@ use std::ffi::c_void;
 use anyhow::Result;
 use libduckdb_sys::{
     duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+    duckdb_list_entry, duckdb_vector_size, idx_t,
 };
+use serde_json::{json, Value};
 use tracing::debug;
 
 use fluvio::dataplane::record::PartitionError;
-use fluvio::metadata::partition::{PartitionSpec, ReplicaKey};
+use fluvio::metadata::objects::Metadata;
+use fluvio::metadata::partition::{PartitionResolution, PartitionSpec, ReplicaKey};
 
-use fluvio::{Fluvio, FluvioAdmin};
+use fluvio::FluvioAdmin;
 use fluvio_future::task::run_block_on;
 
 use crate::bind::{
     malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
     TableFunction,
 };
+use crate::connect::{add_connect_parameters, ConnectOpt};
 
 pub fn fluvio_admin_partition_function_def() -> TableFunction {
     let table_function = TableFunction::new();
     table_function.set_name("fluvio_partitions");
+    add_connect_parameters(&table_function);
 
     table_function.set_function(Some(partition_read));
     table_function.set_init(Some(partition_init));
@@ -35,11 +40,15 @@ struct FluvioAdminInner {
 #[repr(C)]
 struct TopicBindDataStruct(*mut FluvioAdminInner);
 
-#[repr(C)]
-struct TopicInitDataStruct {
-    done: bool,
+/// partitions are listed on first read and returned in chunks of vector size
+struct PartitionScanInner {
+    partitions: Option<Vec<Metadata<PartitionSpec>>>,
+    next_row: usize,
 }
 
+#[repr(C)]
+struct TopicInitDataStruct(*mut PartitionScanInner);
+
 #[no_mangle]
 unsafe extern "C" fn partition_bind(bind_ptr: duckdb_bind_info) {
     let bind_info = BindInfo::from(bind_ptr);
@@ -51,11 +60,26 @@ unsafe extern "C" fn partition_bind(bind_ptr: duckdb_bind_info) {
 unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
     bind_info.add_result_column("topic", LogicalType::new(DuckDBTypeEnum::Varchar));
     bind_info.add_result_column("partition", LogicalType::new(DuckDBTypeEnum::Varchar));
-    bind_info.add_result_column("LEO", LogicalType::new(DuckDBTypeEnum::Integer));
-    //  bind_info.add_result_column("SIZE", LogicalType::new(DuckDBTypeEnum::Integer));
-
+    bind_info.add_result_column("LEO", LogicalType::new(DuckDBTypeEnum::Bigint));
+    bind_info.add_result_column("leader", LogicalType::new(DuckDBTypeEnum::Integer));
+    bind_info.add_result_column(
+        "replicas",
+        LogicalType::list(&LogicalType::new(DuckDBTypeEnum::Integer)),
+    );
+    bind_info.add_result_column("resolution", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("HW", LogicalType::new(DuckDBTypeEnum::Bigint));
+    // records not yet committed to all in-sync replicas
+    bind_info.add_result_column("lag", LogicalType::new(DuckDBTypeEnum::Bigint));
+    // size in bytes, null if not reported by SPU
+    bind_info.add_result_column("SIZE", LogicalType::new(DuckDBTypeEnum::Bigint));
+    // number of live replicas
+    bind_info.add_result_column("LSR", LogicalType::new(DuckDBTypeEnum::Uinteger));
+    // JSON array of follower offsets
+    bind_info.add_result_column("followers", LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    let connect_opt = ConnectOpt::from_bind_info(bind_info)?;
     let admin = run_block_on(async {
-        let fluvio = Fluvio::connect().await?;
+        let fluvio = connect_opt.connect().await?;
         let admin = fluvio.admin().await;
         Ok(admin) as Result<FluvioAdmin>
     })?;
@@ -72,7 +96,8 @@ unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
 }
 
 unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
-    // let actual = v.cast::<MyBindDataStruct>();
+    let actual = v.cast::<TopicBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
     duckdb_free(v);
 }
 
@@ -80,9 +105,19 @@ unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
 unsafe extern "C" fn partition_init(info: duckdb_init_info) {
     let info = InitInfo::from(info);
 
-    let mut my_init_data = malloc_struct::<TopicInitDataStruct>();
-    (*my_init_data).done = false;
-    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
+    let scan_inner = Box::new(PartitionScanInner {
+        partitions: None,
+        next_row: 0,
+    });
+    let my_init_data = malloc_struct::<TopicInitDataStruct>();
+    (*my_init_data).0 = Box::into_raw(scan_inner);
+    info.set_init_data(my_init_data.cast(), Some(drop_my_init_data_struct));
+}
+
+unsafe extern "C" fn drop_my_init_data_struct(v: *mut c_void) {
+    let actual = v.cast::<TopicInitDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
 }
 
 #[no_mangle]
@@ -97,17 +132,18 @@ unsafe extern "C" fn partition_read(info: duckdb_function_info, chunk: duckdb_da
 unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
     let bind_data = info.get_bind_data::<TopicBindDataStruct>();
     let inner = &mut *(*bind_data).0;
-    let mut init_data = info.get_init_data::<TopicInitDataStruct>();
-    if (*init_data).done {
-        output.set_size(0);
-        return Ok(());
-    }
+    let init_data = info.get_init_data::<TopicInitDataStruct>();
+    let scan = &mut *(*init_data).0;
 
-    let admin = inner.admin.as_ref();
-    let partitions = run_block_on(async { admin.all::<PartitionSpec>().await })?;
+    if scan.partitions.is_none() {
+        let admin = inner.admin.as_ref();
+        scan.partitions = Some(run_block_on(async { admin.all::<PartitionSpec>().await })?);
+    }
+    let partitions = &scan.partitions.as_deref().unwrap_or_default()[scan.next_row..];
+    let chunk_len = partitions.len().min(duckdb_vector_size() as usize);
 
-    let mut row = 0;
-    for partition in partitions {
+    for (row, partition) in partitions[..chunk_len].iter().enumerate() {
+        let row = row as idx_t;
         // name
 
         let (topic, partition_key) = {
@@ -128,13 +164,77 @@ unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
         partition_vector.assign_string_element(row, partition_key.as_bytes());
 
         let leo_vector = output.get_vector(2);
-        leo_vector.set_data(row as usize, partition.status.leader.leo as u32);
-
-        row += 1;
+        leo_vector.set_data(row as usize, partition.status.leader.leo);
+
+        let leader_vector = output.get_vector(3);
+        leader_vector.set_data(row as usize, partition.spec.leader);
+
+        // spu ids are appended to child of list vector
+        let replicas = &partition.spec.replicas;
+        let replicas_vector = output.get_vector::<duckdb_list_entry>(4);
+        let offset = replicas_vector.list_size();
+        replicas_vector.set_list_size(offset + replicas.len())?;
+        let spu_vector = replicas_vector.list_child();
+        for (index, spu) in replicas.iter().enumerate() {
+            spu_vector.set_data(offset + index, *spu);
+        }
+        replicas_vector.set_data(
+            row as usize,
+            duckdb_list_entry {
+                offset: offset as u64,
+                length: replicas.len() as u64,
+            },
+        );
+
+        let status = &partition.status;
+        let resolution_vector = output.get_vector(5);
+        resolution_vector
+            .assign_string_element(row, resolution_label(&status.resolution).as_bytes());
+
+        let hw_vector = output.get_vector(6);
+        hw_vector.set_data(row as usize, status.leader.hw);
+
+        let lag_vector = output.get_vector(7);
+        lag_vector.set_data(row as usize, status.leader.leo - status.leader.hw);
+
+        // negative size means error or not supported
+        let size_vector = output.get_vector(8);
+        if status.size >= 0 {
+            size_vector.set_data(row as usize, status.size);
+        } else {
+            size_vector.set_null(row as usize);
+        }
+
+        let lsr_vector = output.get_vector(9);
+        lsr_vector.set_data(row as usize, status.lsr);
+
+        let followers: Vec<Value> = status
+            .replicas
+            .iter()
+            .map(|replica| {
+                json!({
+                    "spu": replica.spu,
+                    "hw": replica.hw,
+                    "leo": replica.leo,
+                    "lag": replica.leader_lag(&status.leader),
+                })
+            })
+            .collect();
+        let followers_vector = output.get_vector(10);
+        followers_vector.assign_string_element(row, Value::from(followers).to_string().as_bytes());
     }
 
-    output.set_size(row);
-    (*init_data).done = true;
+    output.set_size(chunk_len as u64);
+    scan.next_row += chunk_len;
 
     Ok(())
 }
+
+fn resolution_label(resolution: &PartitionResolution) -> &'static str {
+    match resolution {
+        PartitionResolution::Offline => "offline",
+        PartitionResolution::Online => "online",
+        PartitionResolution::LeaderOffline => "leader_offline",
+        PartitionResolution::ElectionLeaderFound => "election_leader_found",
+    }
+}
@@ -0,0 +1,358 @@
+use std::boxed::Box;
+use std::ffi::c_void;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use libduckdb_sys::{
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+    duckdb_string_t, duckdb_type, idx_t,
+};
+use serde_json::{Map, Number, Value};
+use tracing::debug;
+
+use fluvio::{Fluvio, FluvioConfig, RecordKey, TopicProducer};
+use fluvio_future::task::run_block_on;
+
+use crate::bind::{
+    malloc_struct, BindInfo, DataChunk, Database, DuckDBTypeEnum, FunctionInfo, InitInfo,
+    LogicalType, TableFunction, Vector,
+};
+use crate::connect::{add_connect_parameters, ConnectOpt};
+
+/// produce result of a query into a topic
+pub fn fluvio_produce_function_def(db: &Database) -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_produce");
+    add_connect_parameters(&table_function);
+
+    // first parameter is topic name, second is query which rows are produced
+    table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
+    table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
+    // column used as record key
+    table_function.add_named_parameter("key", &LogicalType::new(DuckDBTypeEnum::Varchar));
+    table_function.add_named_parameter("format", &LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    // query is run on separate connection to same database
+    table_function.set_extra_info(db.as_ptr().cast(), None);
+    table_function.set_function(Some(produce_read));
+    table_function.set_init(Some(produce_init));
+    table_function.set_bind(Some(produce_bind));
+    table_function
+}
+
+/// how a row is serialized into record value
+enum ValueFormat {
+    /// JSON object with column names as keys
+    Json,
+    /// first column other than key column as is
+    Raw,
+}
+
+impl TryFrom<&str> for ValueFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(format: &str) -> Result<Self> {
+        match format {
+            "json" => Ok(Self::Json),
+            "raw" => Ok(Self::Raw),
+            _ => Err(anyhow!(
+                "unsupported format: {format}. Supported: json, raw"
+            )),
+        }
+    }
+}
+
+/// resolved cluster config, including endpoint and TLS settings, and topic name
+type ProducerKey = (FluvioConfig, String);
+
+/// producers are cached per cluster and topic, so repeated queries don't connect to the cluster again
+static PRODUCERS: Mutex<Vec<(ProducerKey, Arc<TopicProducer>)>> = Mutex::new(Vec::new());
+
+/// get cached producer for topic or create new one
+async fn topic_producer(key: &ProducerKey) -> Result<Arc<TopicProducer>> {
+    if let Some((_, producer)) = PRODUCERS
+        .lock()
+        .expect("producers lock")
+        .iter()
+        .find(|(cached, _)| cached == key)
+    {
+        return Ok(producer.clone());
+    }
+    let fluvio = Fluvio::connect_with_config(&key.0).await?;
+    let producer = Arc::new(fluvio.topic_producer(&key.1).await?);
+    debug!(endpoint = key.0.endpoint, topic = key.1, "producer created");
+    PRODUCERS
+        .lock()
+        .expect("producers lock")
+        .push((key.clone(), producer.clone()));
+    Ok(producer)
+}
+
+/// remove producer from cache after error, so next query creates new one
+fn evict_producer(key: &ProducerKey) {
+    PRODUCERS
+        .lock()
+        .expect("producers lock")
+        .retain(|(cached, _)| cached != key);
+}
+
+/// flush and close cached producers of topic, or all producers if topic is None
+pub(crate) fn close_producers(topic: Option<&str>) {
+    let closed: Vec<_> = {
+        let mut producers = PRODUCERS.lock().expect("producers lock");
+        let (closed, kept) = producers
+            .drain(..)
+            .partition(|((_, cached), _)| topic.is_none_or(|topic| topic == cached));
+        *producers = kept;
+        closed
+    };
+    for ((config, topic), producer) in closed {
+        if let Err(err) = run_block_on(producer.flush()) {
+            debug!(endpoint = config.endpoint, topic, %err, "flush of closed producer failed");
+        }
+        debug!(endpoint = config.endpoint, topic, "producer closed");
+    }
+}
+
+struct ProduceInner {
+    producer_key: ProducerKey,
+    producer: Arc<TopicProducer>,
+    db: Database,
+    query: String,
+    key_column: Option<String>,
+    format: ValueFormat,
+}
+
+#[repr(C)]
+struct ProduceBindDataStruct(*mut ProduceInner);
+
+#[repr(C)]
+struct ProduceInitDataStruct {
+    done: bool,
+}
+
+const PARM_TOPIC_NAME: u64 = 0;
+const PARM_QUERY: u64 = 1;
+
+#[no_mangle]
+unsafe extern "C" fn produce_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = internal_bind(&bind_info) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
+    let topic = bind_info
+        .get_parameter(PARM_TOPIC_NAME)
+        .get_varchar()
+        .to_str()?
+        .to_owned();
+    // query is wrapped in a subquery when it is run
+    let query = bind_info
+        .get_parameter(PARM_QUERY)
+        .get_varchar()
+        .to_str()?
+        .trim()
+        .trim_end_matches(';')
+        .to_owned();
+    let key_column = match bind_info.get_named_parameter("key") {
+        Some(value) => Some(value.get_varchar().to_str()?.to_owned()),
+        None => None,
+    };
+    let format = match bind_info.get_named_parameter("format") {
+        Some(value) => ValueFormat::try_from(value.get_varchar().to_str()?)?,
+        None => ValueFormat::Json,
+    };
+
+    bind_info.add_result_column("records", LogicalType::new(DuckDBTypeEnum::Ubigint));
+
+    let config = ConnectOpt::from_bind_info(bind_info)?.cluster_config()?;
+    let producer_key = (config, topic);
+    let producer = run_block_on(topic_producer(&producer_key))?;
+
+    let bind_inner = Box::new(ProduceInner {
+        producer_key,
+        producer,
+        db: Database::from(bind_info.get_extra_info::<c_void>().cast()),
+        query,
+        key_column,
+        format,
+    });
+
+    let my_bind_data = malloc_struct::<ProduceBindDataStruct>();
+    (*my_bind_data).0 = Box::into_raw(bind_inner);
+    bind_info.set_bind_data(my_bind_data.cast(), Some(drop_my_bind_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
+    let actual = v.cast::<ProduceBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn produce_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
+
+    let my_init_data = malloc_struct::<ProduceInitDataStruct>();
+    (*my_init_data).done = false;
+    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
+}
+
+#[no_mangle]
+unsafe extern "C" fn produce_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
+    let info = FunctionInfo::from(info);
+    let output = DataChunk::from(chunk);
+    if let Err(err) = internal_read(&info, &output) {
+        info.set_error(&err.to_string());
+    }
+}
+
+/// run query and produce all rows, output number of produced records
+unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
+    let bind_data = info.get_bind_data::<ProduceBindDataStruct>();
+    let inner = &*(*bind_data).0;
+    let init_data = info.get_init_data::<ProduceInitDataStruct>();
+    if (*init_data).done {
+        output.set_size(0);
+        return Ok(());
+    }
+
+    let connection = inner.db.connect()?;
+    // column names and types are taken from an empty result, rows are streamed below
+    let mut columns = connection.query(&format!("select * from ({}) limit 0", inner.query))?;
+    let column_names: Vec<String> = (0..columns.column_count())
+        .map(|col| columns.column_name(col))
+        .collect();
+    let column_types: Vec<duckdb_type> = (0..columns.column_count())
+        .map(|col| columns.column_type(col))
+        .collect();
+    let key_index = match &inner.key_column {
+        Some(key_column) => Some(
+            column_names
+                .iter()
+                .position(|name| name == key_column)
+                .ok_or_else(|| anyhow!("key column `{key_column}` not found in query result"))?,
+        ),
+        None => None,
+    };
+    let raw_index = (0..column_names.len()).find(|col| Some(*col) != key_index);
+    if matches!(inner.format, ValueFormat::Raw) && raw_index.is_none() {
+        return Err(anyhow!(
+            "raw format requires a column other than key column"
+        ));
+    }
+
+    // columns are renamed by position, so duplicate names can be selected.
+    // blob is read as is, other types are read as varchar
+    let aliases: Vec<String> = (0..column_names.len())
+        .map(|col| format!("__c{col}"))
+        .collect();
+    let selects: Vec<String> = aliases
+        .iter()
+        .zip(&column_types)
+        .map(|(alias, ty)| {
+            if *ty == DuckDBTypeEnum::Blob as duckdb_type {
+                alias.clone()
+            } else {
+                format!("cast({alias} as varchar)")
+            }
+        })
+        .collect();
+    let mut result = connection.query_streaming(&format!(
+        "select {} from ({}) as __input({})",
+        selects.join(", "),
+        inner.query,
+        aliases.join(", ")
+    ))?;
+
+    let produced = run_block_on(async {
+        let mut records: u64 = 0;
+        // rows are produced one chunk at a time, so query result is not materialized
+        while let Some(chunk) = result.fetch_chunk()? {
+            let vectors: Vec<Vector<duckdb_string_t>> = (0..column_names.len())
+                .map(|col| chunk.get_vector(col as idx_t))
+                .collect();
+            for row in 0..chunk.size() {
+                let key = match key_index.and_then(|col| vectors[col].get_bytes(row)) {
+                    Some(key) => RecordKey::from(key.to_vec()),
+                    None => RecordKey::NULL,
+                };
+                let value = match inner.format {
+                    ValueFormat::Json => {
+                        let mut object = Map::new();
+                        for (col, name) in column_names.iter().enumerate() {
+                            object.insert(
+                                name.clone(),
+                                json_value(vectors[col].get_bytes(row), column_types[col]),
+                            );
+                        }
+                        serde_json::to_vec(&object)?
+                    }
+                    ValueFormat::Raw => raw_index
+                        .and_then(|col| vectors[col].get_bytes(row))
+                        .map(<[u8]>::to_vec)
+                        .unwrap_or_default(),
+                };
+                inner.producer.send(key, value).await?;
+            }
+            records += chunk.size() as u64;
+        }
+        inner.producer.flush().await?;
+        Ok(records) as Result<u64>
+    });
+    if produced.is_err() {
+        evict_producer(&inner.producer_key);
+    }
+    let records = produced?;
+    debug!(records, "records produced");
+
+    let records_vector = output.get_vector(0);
+    records_vector.set_data(0, records);
+    output.set_size(1);
+    (*init_data).done = true;
+
+    Ok(())
+}
+
+/// column types written as JSON numbers
+const NUMBER_TYPES: &[DuckDBTypeEnum] = &[
+    DuckDBTypeEnum::Tinyint,
+    DuckDBTypeEnum::Smallint,
+    DuckDBTypeEnum::Integer,
+    DuckDBTypeEnum::Bigint,
+    DuckDBTypeEnum::Utinyint,
+    DuckDBTypeEnum::Usmallint,
+    DuckDBTypeEnum::Uinteger,
+    DuckDBTypeEnum::Ubigint,
+    DuckDBTypeEnum::Hugeint,
+    DuckDBTypeEnum::Float,
+    DuckDBTypeEnum::Double,
+    DuckDBTypeEnum::Decimal,
+];
+
+/// convert value read as varchar to JSON, numbers and booleans keep their JSON types
+/// and other types are written as strings
+fn json_value(bytes: Option<&[u8]>, ty: duckdb_type) -> Value {
+    let text = match bytes {
+        Some(bytes) => String::from_utf8_lossy(bytes).to_string(),
+        None => return Value::Null,
+    };
+    if ty == DuckDBTypeEnum::Boolean as duckdb_type {
+        Value::Bool(text == "true")
+    } else if NUMBER_TYPES
+        .iter()
+        .any(|number| number.clone() as duckdb_type == ty)
+    {
+        // nan and inf are not valid JSON numbers
+        match text.parse::<Number>() {
+            Ok(number) => Value::Number(number),
+            Err(_) => Value::String(text),
+        }
+    } else {
+        Value::String(text)
+    }
+}
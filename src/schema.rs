@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::bind::DuckDBTypeEnum;
+use crate::consume::ColumnType;
+
+/// type of json values seen at same path, widened as more records are sampled
+#[derive(Debug, Clone, PartialEq)]
+enum JsonType {
+    Null,
+    Boolean,
+    Bigint,
+    /// integer larger than bigint
+    Ubigint,
+    /// both negative integers and integers larger than bigint
+    Hugeint,
+    Double,
+    /// RFC 3339 string
+    Timestamp,
+    Varchar,
+    List(Box<JsonType>),
+    /// fields in order of first appearance
+    Object(Vec<(String, JsonType)>),
+}
+
+impl JsonType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Boolean,
+            Value::Number(n) if n.is_i64() => Self::Bigint,
+            Value::Number(n) if n.is_u64() => Self::Ubigint,
+            Value::Number(_) => Self::Double,
+            Value::String(s) if DateTime::parse_from_rfc3339(s).is_ok() => Self::Timestamp,
+            Value::String(_) => Self::Varchar,
+            Value::Array(items) => Self::List(Box::new(
+                items.iter().map(Self::of).fold(Self::Null, Self::merge),
+            )),
+            Value::Object(object) => Self::Object(
+                object
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Self::of(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// widest type compatible with both, varchar if there is none
+    fn merge(self, other: Self) -> Self {
+        use JsonType::*;
+
+        match (self, other) {
+            (Null, ty) | (ty, Null) => ty,
+            (List(item), List(other_item)) => List(Box::new(item.merge(*other_item))),
+            (Object(mut fields), Object(other_fields)) => {
+                for (name, other_ty) in other_fields {
+                    match fields.iter_mut().find(|(field, _)| *field == name) {
+                        Some((_, ty)) => *ty = std::mem::replace(ty, Null).merge(other_ty),
+                        None => fields.push((name, other_ty)),
+                    }
+                }
+                Object(fields)
+            }
+            (ty, other) if ty == other => ty,
+            (Bigint | Ubigint | Hugeint, Bigint | Ubigint | Hugeint) => Hugeint,
+            (Bigint | Ubigint | Hugeint | Double, Bigint | Ubigint | Hugeint | Double) => Double,
+            _ => Varchar,
+        }
+    }
+
+    /// column type, objects which are not flattened are json text
+    fn column_type(&self) -> ColumnType {
+        match self {
+            Self::Null | Self::Varchar | Self::Object(_) => DuckDBTypeEnum::Varchar.into(),
+            Self::Boolean => DuckDBTypeEnum::Boolean.into(),
+            Self::Bigint => DuckDBTypeEnum::Bigint.into(),
+            Self::Ubigint => DuckDBTypeEnum::Ubigint.into(),
+            Self::Hugeint => DuckDBTypeEnum::Hugeint.into(),
+            Self::Double => DuckDBTypeEnum::Double.into(),
+            Self::Timestamp => DuckDBTypeEnum::TimestampMs.into(),
+            Self::List(item) => ColumnType::List(Box::new(item.column_type())),
+        }
+    }
+}
+
+/// infer column mappings from sampled record values, in the same form as `-c name:type=path`.
+/// fields of nested objects are flattened into columns named by path joined with `_`
+pub(crate) fn infer_columns(values: &[Vec<u8>]) -> Result<Vec<(String, String)>> {
+    let ty = values
+        .iter()
+        .filter_map(|value| serde_json::from_slice::<Value>(value).ok())
+        .map(|value| JsonType::of(&value))
+        .fold(JsonType::Null, JsonType::merge);
+
+    let fields = match ty {
+        JsonType::Object(fields) if !fields.is_empty() => fields,
+        _ => {
+            return Err(anyhow!(
+                "unable to infer schema from {} records, values must be JSON objects",
+                values.len()
+            ))
+        }
+    };
+
+    let mut columns = vec![];
+    flatten(&fields, &mut vec![], &mut columns);
+    debug!(?columns, "inferred columns");
+    Ok(columns)
+}
+
+fn flatten(
+    fields: &[(String, JsonType)],
+    path: &mut Vec<String>,
+    columns: &mut Vec<(String, String)>,
+) {
+    for (name, ty) in fields {
+        // quote can't be used in selector
+        if name.contains('"') {
+            debug!(name, "skip field");
+            continue;
+        }
+        path.push(name.clone());
+        match ty {
+            JsonType::Object(fields) if !fields.is_empty() => flatten(fields, path, columns),
+            _ => {
+                // colon separates type from column name
+                let mut column = path.join("_").replace(':', "_");
+                // flattened names can collide, as `a_b` and `a.b`, so later ones get a suffix
+                let base = column.clone();
+                let mut suffix = 1;
+                while columns.iter().any(|(name_ty, _)| {
+                    name_ty.split_once(':').map(|(name, _)| name) == Some(&column)
+                }) {
+                    suffix += 1;
+                    column = format!("{base}_{suffix}");
+                }
+                let selector = path
+                    .iter()
+                    .map(|key| format!("\"{key}\""))
+                    .collect::<Vec<_>>()
+                    .join(".");
+                columns.push((format!("{column}:{}", ty.column_type()), selector));
+            }
+        }
+        path.pop();
+    }
+}
@@ -0,0 +1,308 @@
+use std::boxed::Box;
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use libduckdb_sys::{
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+    duckdb_string_t, duckdb_type, duckdb_vector_size, idx_t,
+};
+use serde_json::Value;
+use tracing::debug;
+
+use fluvio::dataplane::record::Record;
+use fluvio_smartengine::metrics::SmartModuleChainMetrics;
+use fluvio_smartengine::transformation::TransformationConfig;
+use fluvio_smartengine::{
+    SmartEngine, SmartModuleChainBuilder, SmartModuleChainInstance, SmartModuleConfig,
+    SmartModuleInitialData,
+};
+use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
+
+use crate::bind::{
+    malloc_struct, BindInfo, Connection, DataChunk, Database, DuckDBTypeEnum, FunctionInfo,
+    InitInfo, LogicalType, QueryResult, TableFunction,
+};
+
+/// run smartmodules locally over result of a query
+pub fn fluvio_smartmodule_apply_function_def(db: &Database) -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_smartmodule_apply");
+
+    // first parameter is query, first column of the result is used as record value
+    table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
+    // second parameter is path to smartmodule wasm file or transforms file
+    table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
+    table_function.add_named_parameter("params", &LogicalType::new(DuckDBTypeEnum::Varchar));
+    table_function.add_named_parameter(
+        "aggregate_initial",
+        &LogicalType::new(DuckDBTypeEnum::Varchar),
+    );
+
+    // query is run on separate connection to same database
+    table_function.set_extra_info(db.as_ptr().cast(), None);
+    table_function.set_function(Some(smartmodule_apply_read));
+    table_function.set_init(Some(smartmodule_apply_init));
+    table_function.set_bind(Some(smartmodule_apply_bind));
+    table_function
+}
+
+struct SmartModuleApplyInner {
+    /// query returning record values in first column
+    query: String,
+    smartmodule: PathBuf,
+    params: BTreeMap<String, String>,
+    aggregate_initial: Option<Vec<u8>>,
+}
+
+#[repr(C)]
+struct SmartModuleApplyBindDataStruct(*mut SmartModuleApplyInner);
+
+/// query result is streamed through smartmodule chain, one input chunk at a time
+struct SmartModuleApplyState {
+    /// declared before connection, so it is destroyed first
+    result: QueryResult,
+    _connection: Connection,
+    chain: SmartModuleChainInstance,
+    metrics: SmartModuleChainMetrics,
+    /// output records not yet returned
+    records: VecDeque<Vec<u8>>,
+    input_rows: usize,
+    input_done: bool,
+}
+
+impl SmartModuleApplyState {
+    /// process next chunk of query result, set input_done when there are no more rows
+    fn process_next_chunk(&mut self) -> Result<()> {
+        let chunk = match self.result.fetch_chunk()? {
+            Some(chunk) => chunk,
+            None => {
+                debug!(input = self.input_rows, "smartmodule applied");
+                self.input_done = true;
+                return Ok(());
+            }
+        };
+
+        let values = chunk.get_vector::<duckdb_string_t>(0);
+        let batch = (0..chunk.size())
+            .map(|row| match values.get_bytes(row) {
+                Some(value) => Ok(Record::new(value.to_vec())),
+                None => Err(anyhow!(
+                    "query returned NULL value at row {}, filter out NULL values in the query",
+                    self.input_rows + row
+                )),
+            })
+            .collect::<Result<Vec<Record>>>()?;
+        self.input_rows += chunk.size();
+
+        let output = self
+            .chain
+            .process(SmartModuleInput::try_from(batch)?, &self.metrics)?;
+        if let Some(error) = output.error {
+            return Err(anyhow!("smartmodule error: {error}"));
+        }
+        self.records.extend(
+            output
+                .successes
+                .into_iter()
+                .map(|record| record.value().as_ref().to_vec()),
+        );
+        Ok(())
+    }
+}
+
+#[repr(C)]
+struct SmartModuleApplyInitDataStruct(*mut SmartModuleApplyState);
+
+const PARM_QUERY: u64 = 0;
+const PARM_SMARTMODULE: u64 = 1;
+
+#[no_mangle]
+unsafe extern "C" fn smartmodule_apply_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = internal_bind(&bind_info) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
+    let query = bind_info.get_parameter(PARM_QUERY).get_varchar();
+    let smartmodule = bind_info.get_parameter(PARM_SMARTMODULE).get_varchar();
+
+    let params = match bind_info.get_named_parameter("params") {
+        Some(value) => {
+            let json = value.get_varchar();
+            let entries: serde_json::Map<String, Value> = serde_json::from_str(json.to_str()?)
+                .map_err(|err| anyhow!("parameter `params` must be a JSON object: {err}"))?;
+            entries
+                .into_iter()
+                .map(|(key, entry)| match entry {
+                    Value::String(s) => (key, s),
+                    other => (key, other.to_string()),
+                })
+                .collect()
+        }
+        None => BTreeMap::new(),
+    };
+    let aggregate_initial = bind_info
+        .get_named_parameter("aggregate_initial")
+        .map(|value| value.get_varchar().into_bytes());
+
+    // column types are taken from an empty result, rows are read in scan
+    let query = query.to_str()?.trim().trim_end_matches(';').to_owned();
+    let db = Database::from(bind_info.get_extra_info::<c_void>().cast());
+    let connection = db.connect()?;
+    let mut result = connection.query(&format!("select * from ({query}) limit 0"))?;
+    if result.column_count() == 0 {
+        return Err(anyhow!("query must return at least one column"));
+    }
+
+    // only first column is read, values other than blob are read as varchar.
+    // first column is renamed so it can be selected regardless of its name
+    let (value_type, query) = if result.column_type(0) == DuckDBTypeEnum::Blob as duckdb_type {
+        (
+            DuckDBTypeEnum::Blob,
+            format!("select __value from ({query}) as __input(__value)"),
+        )
+    } else {
+        (
+            DuckDBTypeEnum::Varchar,
+            format!("select cast(__value as varchar) from ({query}) as __input(__value)"),
+        )
+    };
+    bind_info.add_result_column("value", LogicalType::new(value_type));
+
+    let bind_inner = Box::new(SmartModuleApplyInner {
+        query,
+        smartmodule: PathBuf::from(smartmodule.to_str()?),
+        params,
+        aggregate_initial,
+    });
+    let my_bind_data = malloc_struct::<SmartModuleApplyBindDataStruct>();
+    (*my_bind_data).0 = Box::into_raw(bind_inner);
+    bind_info.set_bind_data(my_bind_data.cast(), Some(drop_my_bind_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
+    let actual = v.cast::<SmartModuleApplyBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+/// build smartmodule chain from single wasm file or from transforms file.
+/// smartmodules are loaded from local files so no cluster is needed
+fn build_chain(
+    smartmodule: &Path,
+    params: BTreeMap<String, String>,
+    aggregate_initial: Option<Vec<u8>>,
+) -> Result<SmartModuleChainInstance> {
+    let mut builder = SmartModuleChainBuilder::default();
+    if smartmodule.extension() == Some("wasm".as_ref()) {
+        let mut config = SmartModuleConfig::builder();
+        config.params(params.into());
+        if let Some(accumulator) = aggregate_initial {
+            config.initial_data(SmartModuleInitialData::with_aggregate(accumulator));
+        }
+        builder.add_smart_module(config.build()?, read_wasm(smartmodule)?);
+    } else {
+        let config = TransformationConfig::from_file(smartmodule).map_err(|err| {
+            anyhow!(format!(
+                "unable to process transforms file `{}`: {err}",
+                smartmodule.display()
+            ))
+        })?;
+        // uses are relative to transforms file
+        let base_dir = smartmodule.parent().unwrap_or_else(|| Path::new(""));
+        for step in config.transforms {
+            let wasm = read_wasm(&base_dir.join(PathBuf::from(&step.uses)))?;
+            builder.add_smart_module(SmartModuleConfig::from(step), wasm);
+        }
+    }
+    builder.initialize(&SmartEngine::new())
+}
+
+fn read_wasm(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|err| {
+        anyhow!(format!(
+            "unable to read smartmodule wasm file `{}`: {err}",
+            path.display()
+        ))
+    })
+}
+
+#[no_mangle]
+unsafe extern "C" fn smartmodule_apply_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
+    if let Err(err) = internal_init(&info) {
+        info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_init(info: &InitInfo) -> Result<()> {
+    let bind_data = info.get_bind_data::<SmartModuleApplyBindDataStruct>();
+    let inner = &*(*bind_data).0;
+
+    // chain is created for each scan, so aggregate starts from initial value
+    let chain = build_chain(
+        &inner.smartmodule,
+        inner.params.clone(),
+        inner.aggregate_initial.clone(),
+    )?;
+    debug!("smartmodule chain initialized");
+
+    let db = Database::from(info.get_extra_info::<c_void>().cast());
+    let connection = db.connect()?;
+    let result = connection.query_streaming(&inner.query)?;
+
+    let state = Box::new(SmartModuleApplyState {
+        result,
+        _connection: connection,
+        chain,
+        metrics: SmartModuleChainMetrics::default(),
+        records: VecDeque::new(),
+        input_rows: 0,
+        input_done: false,
+    });
+    let my_init_data = malloc_struct::<SmartModuleApplyInitDataStruct>();
+    (*my_init_data).0 = Box::into_raw(state);
+    info.set_init_data(my_init_data.cast(), Some(drop_my_init_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_init_data_struct(v: *mut c_void) {
+    let actual = v.cast::<SmartModuleApplyInitDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn smartmodule_apply_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
+    let info = FunctionInfo::from(info);
+    let output = DataChunk::from(chunk);
+    if let Err(err) = internal_read(&info, &output) {
+        info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
+    let init_data = info.get_init_data::<SmartModuleApplyInitDataStruct>();
+    let state = &mut *(*init_data).0;
+
+    // filters can drop all records of a chunk, so keep reading until there is output
+    while state.records.is_empty() && !state.input_done {
+        state.process_next_chunk()?;
+    }
+
+    let chunk_len = state.records.len().min(duckdb_vector_size() as usize);
+    let value_vector = output.get_vector(0);
+    for (row, record) in state.records.drain(..chunk_len).enumerate() {
+        value_vector.assign_string_element(row as idx_t, &record);
+    }
+    output.set_size(chunk_len as u64);
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use std::boxed::Box;
+use std::ffi::c_void;
+
+use anyhow::Result;
+use libduckdb_sys::{
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+    duckdb_vector_size, idx_t,
+};
+use tracing::debug;
+
+use fluvio::metadata::objects::Metadata;
+use fluvio::metadata::smartmodule::SmartModuleSpec;
+
+use fluvio::FluvioAdmin;
+use fluvio_future::task::run_block_on;
+
+use crate::bind::{
+    malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
+    TableFunction,
+};
+use crate::connect::{add_connect_parameters, ConnectOpt};
+
+pub fn fluvio_admin_smartmodule_function_def() -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_smartmodules");
+    add_connect_parameters(&table_function);
+
+    table_function.set_function(Some(smartmodule_read));
+    table_function.set_init(Some(smartmodule_init));
+    table_function.set_bind(Some(smartmodule_bind));
+    table_function
+}
+
+struct FluvioAdminInner {
+    admin: Box<FluvioAdmin>,
+}
+
+#[repr(C)]
+struct SmartModuleBindDataStruct(*mut FluvioAdminInner);
+
+/// smartmodules are listed on first read and returned in chunks of vector size
+struct SmartModuleScanInner {
+    smartmodules: Option<Vec<Metadata<SmartModuleSpec>>>,
+    next_row: usize,
+}
+
+#[repr(C)]
+struct SmartModuleInitDataStruct(*mut SmartModuleScanInner);
+
+/// set up duck db table columns
+#[no_mangle]
+unsafe extern "C" fn smartmodule_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = internal_bind(&bind_info) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
+    bind_info.add_result_column("name", LogicalType::new(DuckDBTypeEnum::Varchar));
+    // package metadata, null for smartmodules loaded without package
+    bind_info.add_result_column("package", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("group", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("version", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("api_version", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("description", LogicalType::new(DuckDBTypeEnum::Varchar));
+    // size of wasm payload in bytes
+    bind_info.add_result_column("size", LogicalType::new(DuckDBTypeEnum::Uinteger));
+    // JSON array of declared parameters
+    bind_info.add_result_column("params", LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    let connect_opt = ConnectOpt::from_bind_info(bind_info)?;
+    let admin = run_block_on(async {
+        let fluvio = connect_opt.connect().await?;
+        let admin = fluvio.admin().await;
+        Ok(admin) as Result<FluvioAdmin>
+    })?;
+
+    let boxed_admin = Box::new(admin);
+    let bind_inner = Box::new(FluvioAdminInner { admin: boxed_admin });
+    debug!("admin created");
+
+    let my_bind_data = malloc_struct::<SmartModuleBindDataStruct>();
+    (*my_bind_data).0 = Box::into_raw(bind_inner);
+    bind_info.set_bind_data(my_bind_data.cast(), Some(drop_my_bind_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
+    let actual = v.cast::<SmartModuleBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn smartmodule_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
+
+    let scan_inner = Box::new(SmartModuleScanInner {
+        smartmodules: None,
+        next_row: 0,
+    });
+    let my_init_data = malloc_struct::<SmartModuleInitDataStruct>();
+    (*my_init_data).0 = Box::into_raw(scan_inner);
+    info.set_init_data(my_init_data.cast(), Some(drop_my_init_data_struct));
+}
+
+unsafe extern "C" fn drop_my_init_data_struct(v: *mut c_void) {
+    let actual = v.cast::<SmartModuleInitDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn smartmodule_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
+    let info = FunctionInfo::from(info);
+    let output = DataChunk::from(chunk);
+    if let Err(err) = internal_read(&info, &output) {
+        info.set_error(&err.to_string());
+    }
+}
+
+/// read smartmodule list from fluvio
+unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
+    let bind_data = info.get_bind_data::<SmartModuleBindDataStruct>();
+    let inner = &*(*bind_data).0;
+    let init_data = info.get_init_data::<SmartModuleInitDataStruct>();
+    let scan = &mut *(*init_data).0;
+
+    if scan.smartmodules.is_none() {
+        // list summary only, so wasm payloads are not downloaded
+        let admin = inner.admin.as_ref();
+        scan.smartmodules = Some(run_block_on(async {
+            admin
+                .list_with_params::<SmartModuleSpec, String>(vec![], true)
+                .await
+        })?);
+    }
+    let smartmodules = &scan.smartmodules.as_deref().unwrap_or_default()[scan.next_row..];
+    let chunk_len = smartmodules.len().min(duckdb_vector_size() as usize);
+
+    for (row, smartmodule) in smartmodules[..chunk_len].iter().enumerate() {
+        let row = row as idx_t;
+        let name_vector = output.get_vector(0);
+        name_vector.assign_string_element(row, smartmodule.name.as_bytes());
+
+        let meta = smartmodule.spec.meta.as_ref();
+        let package = meta.map(|meta| &meta.package);
+        set_optional_string(output, 1, row, package.map(|package| package.name.clone()));
+        set_optional_string(output, 2, row, package.map(|package| package.group.clone()));
+        set_optional_string(
+            output,
+            3,
+            row,
+            package.map(|package| package.version.to_string()),
+        );
+        set_optional_string(
+            output,
+            4,
+            row,
+            package.map(|package| package.api_version.to_string()),
+        );
+        set_optional_string(
+            output,
+            5,
+            row,
+            package.and_then(|package| package.description.clone()),
+        );
+
+        let size_vector = output.get_vector(6);
+        match &smartmodule.spec.summary {
+            Some(summary) => size_vector.set_data(row as usize, summary.wasm_length),
+            None => size_vector.set_null(row as usize),
+        }
+
+        set_optional_string(
+            output,
+            7,
+            row,
+            meta.map(|meta| serde_json::to_string(&meta.params))
+                .transpose()?,
+        );
+    }
+
+    output.set_size(chunk_len as u64);
+    scan.next_row += chunk_len;
+
+    Ok(())
+}
+
+/// write string or null to column
+fn set_optional_string(output: &DataChunk, column: idx_t, row: idx_t, value: Option<String>) {
+    let vector = output.get_vector(column);
+    match value {
+        Some(value) => vector.assign_string_element(row, value.as_bytes()),
+        None => vector.set_null(row as usize),
+    }
+}
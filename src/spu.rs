@@ -0,0 +1,169 @@
+use std::boxed::Box;
+use std::ffi::c_void;
+
+use anyhow::Result;
+use libduckdb_sys::{
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+    duckdb_vector_size, idx_t,
+};
+use tracing::debug;
+
+use fluvio::metadata::objects::Metadata;
+use fluvio::metadata::spu::SpuSpec;
+
+use fluvio::FluvioAdmin;
+use fluvio_future::task::run_block_on;
+
+use crate::bind::{
+    malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
+    TableFunction,
+};
+use crate::connect::{add_connect_parameters, ConnectOpt};
+
+pub fn fluvio_admin_spu_function_def() -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_spus");
+    add_connect_parameters(&table_function);
+
+    table_function.set_function(Some(spu_read));
+    table_function.set_init(Some(spu_init));
+    table_function.set_bind(Some(spu_bind));
+    table_function
+}
+
+struct FluvioAdminInner {
+    admin: Box<FluvioAdmin>,
+}
+
+#[repr(C)]
+struct SpuBindDataStruct(*mut FluvioAdminInner);
+
+/// spus are listed on first read and returned in chunks of vector size
+struct SpuScanInner {
+    spus: Option<Vec<Metadata<SpuSpec>>>,
+    next_row: usize,
+}
+
+#[repr(C)]
+struct SpuInitDataStruct(*mut SpuScanInner);
+
+/// set up duck db table columns
+#[no_mangle]
+unsafe extern "C" fn spu_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = internal_bind(&bind_info) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
+    bind_info.add_result_column("id", LogicalType::new(DuckDBTypeEnum::Integer));
+    bind_info.add_result_column("name", LogicalType::new(DuckDBTypeEnum::Varchar));
+    // managed or custom
+    bind_info.add_result_column("type", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("public_endpoint", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column(
+        "private_endpoint",
+        LogicalType::new(DuckDBTypeEnum::Varchar),
+    );
+    bind_info.add_result_column("rack", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("status", LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    let connect_opt = ConnectOpt::from_bind_info(bind_info)?;
+    let admin = run_block_on(async {
+        let fluvio = connect_opt.connect().await?;
+        let admin = fluvio.admin().await;
+        Ok(admin) as Result<FluvioAdmin>
+    })?;
+
+    let boxed_admin = Box::new(admin);
+    let bind_inner = Box::new(FluvioAdminInner { admin: boxed_admin });
+    debug!("admin created");
+
+    let my_bind_data = malloc_struct::<SpuBindDataStruct>();
+    (*my_bind_data).0 = Box::into_raw(bind_inner);
+    bind_info.set_bind_data(my_bind_data.cast(), Some(drop_my_bind_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
+    let actual = v.cast::<SpuBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn spu_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
+
+    let scan_inner = Box::new(SpuScanInner {
+        spus: None,
+        next_row: 0,
+    });
+    let my_init_data = malloc_struct::<SpuInitDataStruct>();
+    (*my_init_data).0 = Box::into_raw(scan_inner);
+    info.set_init_data(my_init_data.cast(), Some(drop_my_init_data_struct));
+}
+
+unsafe extern "C" fn drop_my_init_data_struct(v: *mut c_void) {
+    let actual = v.cast::<SpuInitDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn spu_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
+    let info = FunctionInfo::from(info);
+    let output = DataChunk::from(chunk);
+    if let Err(err) = internal_read(&info, &output) {
+        info.set_error(&err.to_string());
+    }
+}
+
+/// read spu list from fluvio
+unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
+    let bind_data = info.get_bind_data::<SpuBindDataStruct>();
+    let inner = &*(*bind_data).0;
+    let init_data = info.get_init_data::<SpuInitDataStruct>();
+    let scan = &mut *(*init_data).0;
+
+    if scan.spus.is_none() {
+        let admin = inner.admin.as_ref();
+        scan.spus = Some(run_block_on(async { admin.all::<SpuSpec>().await })?);
+    }
+    let spus = &scan.spus.as_deref().unwrap_or_default()[scan.next_row..];
+    let chunk_len = spus.len().min(duckdb_vector_size() as usize);
+
+    for (row, spu) in spus[..chunk_len].iter().enumerate() {
+        let row = row as idx_t;
+        let id_vector = output.get_vector(0);
+        id_vector.set_data(row as usize, spu.spec.id);
+
+        let name_vector = output.get_vector(1);
+        name_vector.assign_string_element(row, spu.name.as_bytes());
+
+        let type_vector = output.get_vector(2);
+        type_vector.assign_string_element(row, spu.spec.spu_type.type_label().as_bytes());
+
+        let public_vector = output.get_vector(3);
+        public_vector.assign_string_element(row, spu.spec.public_endpoint.addr().as_bytes());
+
+        let private_vector = output.get_vector(4);
+        private_vector.assign_string_element(row, spu.spec.private_endpoint.to_string().as_bytes());
+
+        let rack_vector = output.get_vector(5);
+        match &spu.spec.rack {
+            Some(rack) => rack_vector.assign_string_element(row, rack.as_bytes()),
+            None => rack_vector.set_null(row as usize),
+        }
+
+        let status_vector = output.get_vector(6);
+        status_vector.assign_string_element(row, spu.status.resolution_label().as_bytes());
+    }
+
+    output.set_size(chunk_len as u64);
+    scan.next_row += chunk_len;
+
+    Ok(())
+}
@@ -4,22 +4,26 @@ use std::ffi::c_void;
 use anyhow::Result;
 use libduckdb_sys::{
     duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+    duckdb_vector_size, idx_t,
 };
 use tracing::debug;
 
+use fluvio::metadata::objects::Metadata;
 use fluvio::metadata::topic::TopicSpec;
 
-use fluvio::{Fluvio, FluvioAdmin};
+use fluvio::FluvioAdmin;
 use fluvio_future::task::run_block_on;
 
 use crate::bind::{
     malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
     TableFunction,
 };
+use crate::connect::{add_connect_parameters, ConnectOpt};
 
 pub fn fluvio_admin_topic_function_def() -> TableFunction {
     let table_function = TableFunction::new();
     table_function.set_name("fluvio_topics");
+    add_connect_parameters(&table_function);
 
     table_function.set_function(Some(topic_read));
     table_function.set_init(Some(topic_init));
@@ -34,11 +38,15 @@ struct FluvioAdminInner {
 #[repr(C)]
 struct TopicBindDataStruct(*mut FluvioAdminInner);
 
-#[repr(C)]
-struct TopicInitDataStruct {
-    done: bool,
+/// topics are listed on first read and returned in chunks of vector size
+struct TopicScanInner {
+    topics: Option<Vec<Metadata<TopicSpec>>>,
+    next_row: usize,
 }
 
+#[repr(C)]
+struct TopicInitDataStruct(*mut TopicScanInner);
+
 /// set up duck db table columns
 #[no_mangle]
 unsafe extern "C" fn topic_bind(bind_ptr: duckdb_bind_info) {
@@ -51,9 +59,24 @@ unsafe extern "C" fn topic_bind(bind_ptr: duckdb_bind_info) {
 unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
     bind_info.add_result_column("name", LogicalType::new(DuckDBTypeEnum::Varchar));
     bind_info.add_result_column("partitions", LogicalType::new(DuckDBTypeEnum::Integer));
-
+    // computed or assigned
+    bind_info.add_result_column("type", LogicalType::new(DuckDBTypeEnum::Varchar));
+    // null for assigned topics
+    bind_info.add_result_column("replication", LogicalType::new(DuckDBTypeEnum::Integer));
+    bind_info.add_result_column("ignore_rack", LogicalType::new(DuckDBTypeEnum::Boolean));
+    bind_info.add_result_column("retention_secs", LogicalType::new(DuckDBTypeEnum::Uinteger));
+    bind_info.add_result_column("segment_size", LogicalType::new(DuckDBTypeEnum::Uinteger));
+    bind_info.add_result_column(
+        "max_partition_size",
+        LogicalType::new(DuckDBTypeEnum::Ubigint),
+    );
+    bind_info.add_result_column("compression", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("status", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("reason", LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    let connect_opt = ConnectOpt::from_bind_info(bind_info)?;
     let admin = run_block_on(async {
-        let fluvio = Fluvio::connect().await?;
+        let fluvio = connect_opt.connect().await?;
         let admin = fluvio.admin().await;
         Ok(admin) as Result<FluvioAdmin>
     })?;
@@ -70,7 +93,8 @@ unsafe fn internal_bind(bind_info: &BindInfo) -> Result<()> {
 }
 
 unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
-    // let actual = v.cast::<MyBindDataStruct>();
+    let actual = v.cast::<TopicBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
     duckdb_free(v);
 }
 
@@ -78,9 +102,19 @@ unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
 unsafe extern "C" fn topic_init(info: duckdb_init_info) {
     let info = InitInfo::from(info);
 
-    let mut my_init_data = malloc_struct::<TopicInitDataStruct>();
-    (*my_init_data).done = false;
-    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
+    let scan_inner = Box::new(TopicScanInner {
+        topics: None,
+        next_row: 0,
+    });
+    let my_init_data = malloc_struct::<TopicInitDataStruct>();
+    (*my_init_data).0 = Box::into_raw(scan_inner);
+    info.set_init_data(my_init_data.cast(), Some(drop_my_init_data_struct));
+}
+
+unsafe extern "C" fn drop_my_init_data_struct(v: *mut c_void) {
+    let actual = v.cast::<TopicInitDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
 }
 
 #[no_mangle]
@@ -99,28 +133,69 @@ unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
 
     let bind_data = info.get_bind_data::<TopicBindDataStruct>();
     let inner = &mut *(*bind_data).0;
-    let mut init_data = info.get_init_data::<TopicInitDataStruct>();
-    if (*init_data).done {
-        output.set_size(0);
-        return Ok(());
-    }
+    let init_data = info.get_init_data::<TopicInitDataStruct>();
+    let scan = &mut *(*init_data).0;
 
-    let admin = inner.admin.as_ref();
-    let topics = run_block_on(async { admin.all::<TopicSpec>().await })?;
+    if scan.topics.is_none() {
+        let admin = inner.admin.as_ref();
+        scan.topics = Some(run_block_on(async { admin.all::<TopicSpec>().await })?);
+    }
+    let topics = &scan.topics.as_deref().unwrap_or_default()[scan.next_row..];
+    let chunk_len = topics.len().min(duckdb_vector_size() as usize);
 
-    let mut row = 0;
-    for topic in topics {
+    for (row, topic) in topics[..chunk_len].iter().enumerate() {
+        let row = row as idx_t;
         // name
         let value_vector = output.get_vector(0);
         value_vector.assign_string_element(row, topic.name.as_bytes());
 
         let offset_vector = output.get_vector(1);
         offset_vector.set_data(row as usize, topic.spec.partitions());
-        row += 1;
+
+        let replicas = topic.spec.replicas();
+        let type_vector = output.get_vector(2);
+        type_vector.assign_string_element(row, replicas.type_label().as_bytes());
+
+        let replication_vector = output.get_vector(3);
+        match replicas.replication_factor() {
+            Some(replication) => replication_vector.set_data(row as usize, replication),
+            None => replication_vector.set_null(row as usize),
+        }
+
+        let ignore_rack_vector = output.get_vector(4);
+        ignore_rack_vector.set_data(row as usize, replicas.ignore_rack_assignment());
+
+        let retention_vector = output.get_vector(5);
+        retention_vector.set_data(row as usize, topic.spec.retention_secs());
+
+        // storage config is optional, cluster defaults are used if not set
+        let storage = topic.spec.get_storage();
+        let segment_size_vector = output.get_vector(6);
+        match storage.and_then(|storage| storage.segment_size) {
+            Some(segment_size) => segment_size_vector.set_data(row as usize, segment_size),
+            None => segment_size_vector.set_null(row as usize),
+        }
+        let max_partition_size_vector = output.get_vector(7);
+        match storage.and_then(|storage| storage.max_partition_size) {
+            Some(max_size) => max_partition_size_vector.set_data(row as usize, max_size),
+            None => max_partition_size_vector.set_null(row as usize),
+        }
+
+        let compression_vector = output.get_vector(8);
+        compression_vector.assign_string_element(
+            row,
+            topic.spec.get_compression_type().to_string().as_bytes(),
+        );
+
+        let status_vector = output.get_vector(9);
+        status_vector
+            .assign_string_element(row, topic.status.resolution.resolution_label().as_bytes());
+        let reason_vector = output.get_vector(10);
+        reason_vector.assign_string_element(row, topic.status.reason.as_bytes());
     }
 
-    output.set_size(row);
-    (*init_data).done = true;
+    output.set_size(chunk_len as u64);
+    scan.next_row += chunk_len;
 
     Ok(())
 }
@@ -0,0 +1,223 @@
+use std::boxed::Box;
+use std::ffi::c_void;
+
+use anyhow::{anyhow, Result};
+use libduckdb_sys::{
+    duckdb_bind_info, duckdb_data_chunk, duckdb_free, duckdb_function_info, duckdb_init_info,
+};
+use tracing::debug;
+
+use fluvio::metadata::topic::{CleanupPolicy, SegmentBasedPolicy, TopicSpec};
+use fluvio::FluvioAdmin;
+use fluvio_future::task::run_block_on;
+
+use crate::bind::{
+    malloc_struct, BindInfo, DataChunk, DuckDBTypeEnum, FunctionInfo, InitInfo, LogicalType,
+    TableFunction,
+};
+use crate::connect::{add_connect_parameters, ConnectOpt};
+use crate::produce::close_producers;
+
+/// create topic, e.g. fluvio_create_topic('name', partitions := 3, retention := '7d')
+pub fn fluvio_create_topic_function_def() -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_create_topic");
+    add_connect_parameters(&table_function);
+
+    // first parameter is topic name
+    table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
+    table_function.add_named_parameter("partitions", &LogicalType::new(DuckDBTypeEnum::Integer));
+    table_function.add_named_parameter("replication", &LogicalType::new(DuckDBTypeEnum::Integer));
+    table_function.add_named_parameter("retention", &LogicalType::new(DuckDBTypeEnum::Varchar));
+    table_function.add_named_parameter("ignore_rack", &LogicalType::new(DuckDBTypeEnum::Boolean));
+    table_function.add_named_parameter("dry_run", &LogicalType::new(DuckDBTypeEnum::Boolean));
+
+    table_function.set_function(Some(topic_admin_read));
+    table_function.set_init(Some(topic_admin_init));
+    table_function.set_bind(Some(create_topic_bind));
+    table_function
+}
+
+/// delete topic, e.g. fluvio_delete_topic('name')
+pub fn fluvio_delete_topic_function_def() -> TableFunction {
+    let table_function = TableFunction::new();
+    table_function.set_name("fluvio_delete_topic");
+    add_connect_parameters(&table_function);
+
+    // first parameter is topic name
+    table_function.add_parameter(&LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    table_function.set_function(Some(topic_admin_read));
+    table_function.set_init(Some(topic_admin_init));
+    table_function.set_bind(Some(delete_topic_bind));
+    table_function
+}
+
+/// change to apply to the cluster
+enum TopicRequest {
+    Create { spec: TopicSpec, dry_run: bool },
+    Delete,
+}
+
+struct TopicAdminInner {
+    admin: Box<FluvioAdmin>,
+    name: String,
+    request: TopicRequest,
+}
+
+#[repr(C)]
+struct TopicAdminBindDataStruct(*mut TopicAdminInner);
+
+#[repr(C)]
+struct TopicAdminInitDataStruct {
+    done: bool,
+}
+
+const PARM_TOPIC_NAME: u64 = 0;
+
+#[no_mangle]
+unsafe extern "C" fn create_topic_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = internal_create_bind(&bind_info) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+unsafe fn internal_create_bind(bind_info: &BindInfo) -> Result<()> {
+    let partitions = named_u32(bind_info, "partitions")?.unwrap_or(1);
+    let replication = named_u32(bind_info, "replication")?.unwrap_or(1);
+    let ignore_rack = bind_info
+        .get_named_parameter("ignore_rack")
+        .map(|value| value.get_int64() != 0);
+    let dry_run = bind_info
+        .get_named_parameter("dry_run")
+        .map(|value| value.get_int64() != 0)
+        .unwrap_or(false);
+
+    let mut spec = TopicSpec::new_computed(partitions, replication, ignore_rack);
+    if let Some(value) = bind_info.get_named_parameter("retention") {
+        let retention = value.get_varchar();
+        let retention = humantime::parse_duration(retention.to_str()?)
+            .map_err(|err| anyhow!("invalid retention, expected duration like 7d: {err}"))?;
+        spec.set_cleanup_policy(CleanupPolicy::Segment(SegmentBasedPolicy {
+            time_in_seconds: retention.as_secs().try_into()?,
+        }));
+    }
+
+    bind_topic_request(bind_info, TopicRequest::Create { spec, dry_run })
+}
+
+#[no_mangle]
+unsafe extern "C" fn delete_topic_bind(bind_ptr: duckdb_bind_info) {
+    let bind_info = BindInfo::from(bind_ptr);
+    if let Err(err) = bind_topic_request(&bind_info, TopicRequest::Delete) {
+        bind_info.set_error(&err.to_string());
+    }
+}
+
+/// get named parameter which must be positive integer
+unsafe fn named_u32(bind_info: &BindInfo, name: &str) -> Result<Option<u32>> {
+    match bind_info.get_named_parameter(name) {
+        Some(value) => {
+            let value = value.get_int64();
+            if value < 1 {
+                return Err(anyhow!("{name} must be positive, got: {value}"));
+            }
+            Ok(Some(value.try_into()?))
+        }
+        None => Ok(None),
+    }
+}
+
+unsafe fn bind_topic_request(bind_info: &BindInfo, request: TopicRequest) -> Result<()> {
+    let name = bind_info
+        .get_parameter(PARM_TOPIC_NAME)
+        .get_varchar()
+        .to_str()?
+        .to_owned();
+
+    bind_info.add_result_column("name", LogicalType::new(DuckDBTypeEnum::Varchar));
+    bind_info.add_result_column("status", LogicalType::new(DuckDBTypeEnum::Varchar));
+
+    let connect_opt = ConnectOpt::from_bind_info(bind_info)?;
+    let admin = run_block_on(async {
+        let fluvio = connect_opt.connect().await?;
+        let admin = fluvio.admin().await;
+        Ok(admin) as Result<FluvioAdmin>
+    })?;
+    debug!("admin created");
+
+    let bind_inner = Box::new(TopicAdminInner {
+        admin: Box::new(admin),
+        name,
+        request,
+    });
+
+    let my_bind_data = malloc_struct::<TopicAdminBindDataStruct>();
+    (*my_bind_data).0 = Box::into_raw(bind_inner);
+    bind_info.set_bind_data(my_bind_data.cast(), Some(drop_my_bind_data_struct));
+
+    Ok(())
+}
+
+unsafe extern "C" fn drop_my_bind_data_struct(v: *mut c_void) {
+    let actual = v.cast::<TopicAdminBindDataStruct>();
+    drop(Box::from_raw((*actual).0));
+    duckdb_free(v);
+}
+
+#[no_mangle]
+unsafe extern "C" fn topic_admin_init(info: duckdb_init_info) {
+    let info = InitInfo::from(info);
+
+    let my_init_data = malloc_struct::<TopicAdminInitDataStruct>();
+    (*my_init_data).done = false;
+    info.set_init_data(my_init_data.cast(), Some(duckdb_free));
+}
+
+#[no_mangle]
+unsafe extern "C" fn topic_admin_read(info: duckdb_function_info, chunk: duckdb_data_chunk) {
+    let info = FunctionInfo::from(info);
+    let output = DataChunk::from(chunk);
+    if let Err(err) = internal_read(&info, &output) {
+        info.set_error(&err.to_string());
+    }
+}
+
+/// apply change to the cluster, output topic name and what was done
+unsafe fn internal_read(info: &FunctionInfo, output: &DataChunk) -> Result<()> {
+    let bind_data = info.get_bind_data::<TopicAdminBindDataStruct>();
+    let inner = &*(*bind_data).0;
+    let init_data = info.get_init_data::<TopicAdminInitDataStruct>();
+    if (*init_data).done {
+        output.set_size(0);
+        return Ok(());
+    }
+
+    let admin = inner.admin.as_ref();
+    let status = match &inner.request {
+        TopicRequest::Create { spec, dry_run } => {
+            run_block_on(admin.create(inner.name.clone(), *dry_run, spec.clone()))?;
+            if *dry_run {
+                "dry run"
+            } else {
+                "created"
+            }
+        }
+        TopicRequest::Delete => {
+            run_block_on(admin.delete::<TopicSpec, _>(inner.name.clone()))?;
+            close_producers(Some(&inner.name));
+            "deleted"
+        }
+    };
+    debug!(name = inner.name, status, "topic request done");
+
+    let name_vector = output.get_vector(0);
+    name_vector.assign_string_element(0, inner.name.as_bytes());
+    let status_vector = output.get_vector(1);
+    status_vector.assign_string_element(0, status.as_bytes());
+    output.set_size(1);
+    (*init_data).done = true;
+
+    Ok(())
+}